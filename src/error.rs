@@ -0,0 +1,54 @@
+use crate::maze::Cell;
+
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Error {
+    /// The row and column is masked or outside the maze
+    InvalidCell(u32, u32),
+    /// The cell is in a region disconnected from the start
+    Unreachable(Cell),
+    /// There is no cell at distance zero to start from
+    NoStart,
+    /// Saved or drawn maze data could not be read
+    InvalidFormat(&'static str),
+    /// A colour map was built without any stops
+    EmptyColourMap,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidCell(row, column) => write!(f, "Invalid cell {},{}", row, column),
+            Error::Unreachable(cell) => {
+                write!(f, "Cell {},{} is unreachable", cell.row(), cell.column())
+            }
+            Error::NoStart => f.write_str("No cells at distance zero"),
+            Error::InvalidFormat(reason) => write!(f, "Invalid maze format: {}", reason),
+            Error::EmptyColourMap => f.write_str("Colour map needs at least one stop"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_display() {
+        assert_eq!(Error::InvalidCell(1, 2).to_string(), "Invalid cell 1,2");
+        assert_eq!(Error::NoStart.to_string(), "No cells at distance zero");
+        assert_eq!(
+            Error::InvalidFormat("bad header").to_string(),
+            "Invalid maze format: bad header"
+        );
+        assert_eq!(
+            Error::EmptyColourMap.to_string(),
+            "Colour map needs at least one stop"
+        );
+    }
+}
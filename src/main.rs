@@ -1,59 +1,95 @@
+mod error;
 mod maze;
 mod router;
 mod solver;
 mod util;
 
-use crate::maze::grid::{Compass, Grid};
+use crate::maze::accessible::Braille;
+use crate::maze::animation::Animation;
+use crate::maze::html::Html;
+use crate::maze::sheet::Sheet;
+use crate::maze::spec::{Mask, MazeSpec, RouterKind};
+use crate::maze::style::RenderStyle;
+use crate::maze::terminal::Terminal;
 use crate::maze::Maze;
-use crate::router::binarytree::BinaryTree;
-use crate::router::sidewinder::SideWinder;
 use crate::solver::dijkstra::Dijkstra;
 
+use rand::Rng;
+use std::env;
+
 #[cfg(not(tarpaulin_include))]
 fn main() {
     let mut rng = rand::thread_rng();
+    let small = MazeSpec::new(5, 5)
+        .router(RouterKind::BinaryTree)
+        .mask(Mask::Corners)
+        .seed(rng.gen());
     print!(
-        "{}",
-        Grid::grid(
-            5,
-            5,
-            mask_corners(5, 5),
-            &mut BinaryTree::<Compass>::new_for_compass(&mut rng),
-        )
+        "{}\n{}",
+        small,
+        small.build().expect("Could not build small maze")
     );
 
-    let mut grid = Grid::grid(
-        10,
-        10,
-        Grid::ALLOW_ALL,
-        &mut SideWinder::<Compass>::new_for_compass(&mut rng),
-    );
+    // carve the maze given as the first argument, or a new one that can be carved again
+    let spec = match env::args().nth(1).map(|spec| spec.parse::<MazeSpec>()) {
+        Some(Ok(spec)) => spec,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return;
+        }
+        None => MazeSpec::new(10, 10).seed(rng.gen()),
+    };
+    println!("{}", spec);
+    let mut grid = match spec.recorded() {
+        Ok(grid) => grid,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let (start, goal) = match (grid.cells().first(), grid.cells().last()) {
+        (Some(first), Some(last)) => (first.coords(), last.coords()),
+        _ => {
+            eprintln!("The maze has no cells");
+            return;
+        }
+    };
 
-    grid.apply_distances(Dijkstra::solve(&grid, (0, 0)));
-    grid.draw("target/maze.png")
-        .expect("Could not write `target/maze.png`");
+    let animation = Animation::new(RenderStyle::default(), 1, 50);
+    animation
+        .write("target/carving.gif", animation.carving(&grid))
+        .expect("Could not write `target/carving.gif`");
 
-    print!("{}", grid);
-}
+    grid.apply_distances(Dijkstra::solve(&grid, start).expect("Could not solve maze"));
+    grid.draw("target/maze.png", &RenderStyle::default())
+        .expect("Could not write `target/maze.png`");
+    grid.write_svg("target/maze.svg", &RenderStyle::print(), true, None)
+        .expect("Could not write `target/maze.svg`");
 
-fn mask_corners(rows: u32, columns: u32) -> impl Fn(u32, u32) -> bool {
-    move |r, c| !((r == 0 || r == rows - 1) && (c == 0 || c == columns - 1))
-}
+    let sheet = match Sheet::new(&grid, "Maze", start, goal) {
+        Ok(sheet) => sheet.spec(&spec.to_string()),
+        Err(e) => {
+            eprintln!("Could not solve maze: {}", e);
+            return;
+        }
+    };
+    sheet
+        .write_png("target/puzzle.png", "target/answer.png")
+        .expect("Could not write puzzle sheet");
+    sheet
+        .write_svg("target/puzzle.svg", "target/answer.svg")
+        .expect("Could not write puzzle sheet");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn check_mask_corners() {
-        let f = mask_corners(5, 5);
-
-        assert_eq!(f(0, 0), false);
-        assert_eq!(f(0, 2), true);
-        assert_eq!(f(0, 4), false);
-        assert_eq!(f(2, 2), true);
-        assert_eq!(f(4, 0), false);
-        assert_eq!(f(4, 2), true);
-        assert_eq!(f(4, 4), false);
+    match Html::new(&grid, start, goal) {
+        Ok(html) => html
+            .write("target/maze.html")
+            .expect("Could not write `target/maze.html`"),
+        Err(e) => {
+            eprintln!("Could not solve maze: {}", e);
+            return;
+        }
     }
+
+    print!("{}", Terminal::new(&grid));
+    print!("{}", Braille::new(&grid));
 }
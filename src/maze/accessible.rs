@@ -0,0 +1,228 @@
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::{Cell, Direction, Maze};
+
+use std::fmt;
+
+/// Renderer using Unicode Braille patterns, for refreshable Braille displays
+///
+/// Walls and corners are raised dots, each cell is two dots wide with its walls shared with
+/// its neighbours, and masked cells are solid.
+/// ```
+///     print!("{}", Braille::new(&grid));
+/// ```
+pub struct Braille<'a> {
+    grid: &'a Grid,
+}
+
+#[allow(dead_code)]
+impl<'a> Braille<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Braille { grid }
+    }
+
+    /// Return the raised dots, with a row and column of dots for each wall and cell
+    fn dots(&self) -> Vec<Vec<bool>> {
+        let rows = self.grid.rows() as usize;
+        let columns = self.grid.columns() as usize;
+        let mut dots = vec![vec![false; 2 * columns + 1]; 2 * rows + 1];
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let (y, x) = (2 * row + 1, 2 * column + 1);
+                let cell = self.grid.cell(row as u32, column as u32);
+                // every corner is raised, then walls to the north and west of each position
+                dots[y - 1][x - 1] = true;
+                dots[y - 1][x + 1] = true;
+                dots[y + 1][x - 1] = true;
+                dots[y + 1][x + 1] = true;
+                match cell {
+                    Some(c) => {
+                        let links = self.grid.links(c);
+                        dots[y - 1][x] |= !links.contains(&Compass::North);
+                        dots[y][x - 1] |= !links.contains(&Compass::West);
+                        dots[y][x + 1] |= !links.contains(&Compass::East);
+                        dots[y + 1][x] |= !links.contains(&Compass::South);
+                    }
+                    None => {
+                        dots[y - 1][x] = true;
+                        dots[y][x - 1] = true;
+                        dots[y][x] = true;
+                        dots[y][x + 1] = true;
+                        dots[y + 1][x] = true;
+                    }
+                }
+            }
+        }
+        dots
+    }
+}
+
+impl<'a> fmt::Display for Braille<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // bit for each dot of the 2 by 4 pattern, indexed by row then column
+        const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        let dots = self.dots();
+        let height = dots.len();
+        let width = dots[0].len();
+        let mut s = String::new();
+
+        for top in (0..height).step_by(4) {
+            for left in (0..width).step_by(2) {
+                let mut pattern = 0;
+                for (dy, bits) in BITS.iter().enumerate() {
+                    for (dx, bit) in bits.iter().enumerate() {
+                        if dots
+                            .get(top + dy)
+                            .and_then(|r| r.get(left + dx))
+                            .copied()
+                            .unwrap_or(false)
+                        {
+                            pattern |= bit;
+                        }
+                    }
+                }
+                s.push(std::char::from_u32(0x2800 + pattern).expect("Invalid Braille pattern"));
+            }
+            s.push('\n');
+        }
+        f.write_str(&s)
+    }
+}
+
+/// Plain text description of a maze for screen readers, one line per cell listing the
+/// directions that are open
+///
+/// Rows and columns are counted from one, starting at the top left.
+pub struct Description<'a> {
+    grid: &'a Grid,
+    start: Option<Cell>,
+    goal: Option<Cell>,
+}
+
+#[allow(dead_code)]
+impl<'a> Description<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Description {
+            grid,
+            start: None,
+            goal: None,
+        }
+    }
+
+    pub fn start(mut self, cell: Cell) -> Self {
+        self.start = Some(cell);
+        self
+    }
+
+    pub fn goal(mut self, cell: Cell) -> Self {
+        self.goal = Some(cell);
+        self
+    }
+
+    fn name(direction: Compass) -> &'static str {
+        match direction {
+            Compass::North => "north",
+            Compass::East => "east",
+            Compass::South => "south",
+            Compass::West => "west",
+        }
+    }
+
+    /// Describe one cell, e.g. `Row 1, column 2, start: open east and south.`
+    fn describe(&self, cell: &Cell) -> String {
+        let links = self.grid.links(cell);
+        let open: Vec<&str> = Compass::all()
+            .into_iter()
+            .filter(|d| links.contains(d))
+            .map(Description::name)
+            .collect();
+        let mut line = format!("Row {}, column {}", cell.row() + 1, cell.column() + 1);
+
+        if self.start == Some(*cell) {
+            line.push_str(", start");
+        }
+        if self.goal == Some(*cell) {
+            line.push_str(", goal");
+        }
+        match open.as_slice() {
+            [] => line.push_str(": closed"),
+            [only] => line.push_str(&format!(": open {}, dead end", only)),
+            [rest @ .., last] => line.push_str(&format!(": open {} and {}", rest.join(", "), last)),
+        }
+        line.push('.');
+        line
+    }
+}
+
+impl<'a> fmt::Display for Description<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Maze of {} rows and {} columns.",
+            self.grid.rows(),
+            self.grid.columns()
+        )?;
+        for row in 0..self.grid.rows() {
+            for column in 0..self.grid.columns() {
+                match self.grid.cell(row, column) {
+                    Some(cell) => writeln!(f, "{}", self.describe(cell))?,
+                    None => writeln!(f, "Row {}, column {}: blocked.", row + 1, column + 1)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::internal::NoOp;
+
+    #[test]
+    fn check_braille_single() {
+        let grid = Grid::square(1);
+
+        assert_eq!(Braille::new(&grid).to_string(), "⠯⠇\n");
+    }
+
+    #[test]
+    fn check_braille_linked() {
+        let mut grid = Grid::grid(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+
+        // the wall between the cells is the first column of the second pattern
+        assert_eq!(Braille::new(&grid).to_string(), "⠯⠯⠇\n");
+        grid.link_cell(&cell, Compass::East);
+        assert_eq!(Braille::new(&grid).to_string(), "⠯⠭⠇\n");
+    }
+
+    #[test]
+    fn check_braille_masked() {
+        let grid = Grid::grid(1, 2, |_, c| c == 1, &mut NoOp {});
+
+        assert_eq!(Braille::new(&grid).to_string(), "⠿⠯⠇\n");
+    }
+
+    #[test]
+    fn check_description() {
+        let mut grid = Grid::grid(2, 2, |r, c| r != 1 || c != 1, &mut NoOp {});
+        let cell_00 = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell_00, Compass::East);
+        grid.link_cell(&cell_00, Compass::South);
+        let cell_01 = *grid.cell(0, 1).expect("Missing Cell 0,1");
+
+        assert_eq!(
+            Description::new(&grid)
+                .start(cell_00)
+                .goal(cell_01)
+                .to_string(),
+            "Maze of 2 rows and 2 columns.
+Row 1, column 1, start: open east and south.
+Row 1, column 2, goal: open west, dead end.
+Row 2, column 1: open north, dead end.
+Row 2, column 2: blocked.
+"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::maze::style::RenderStyle;
+use crate::maze::{Direction, Maze};
+use crate::solver::Distances;
+
+use image::gif::{GifEncoder, Repeat};
+use image::{Delay, DynamicImage, Frame, ImageError, ImageResult, RgbImage};
+use std::cmp;
+use std::fs::File;
+
+/// Renders recorded carving and solving steps as frames of an animated GIF
+pub struct Animation {
+    style: RenderStyle,
+    skip: usize,
+    delay: u32,
+}
+
+#[allow(dead_code)]
+impl Animation {
+    /// Build a new animation
+    ///
+    /// # Arguments
+    /// * `style` - sizes and colours to draw each frame with
+    /// * `skip` - number of steps between frames, 1 draws every step
+    /// * `delay` - time each frame is shown for in milliseconds
+    pub fn new(style: RenderStyle, skip: usize, delay: u32) -> Self {
+        Animation {
+            style,
+            skip: cmp::max(1, skip),
+            delay,
+        }
+    }
+
+    /// Return frames replaying the recorded history, starting from a maze with no links
+    ///
+    /// The maze must have been built with recording enabled, e.g. `Grid::recorded`.
+    pub fn carving<T: Direction, M: Maze<T> + Clone>(&self, maze: &M) -> Vec<RgbImage> {
+        let mut canvas = maze.clone();
+        canvas._stop_recording();
+        canvas.clear_distances();
+
+        for cell in maze.cells() {
+            for direction in maze.links(cell) {
+                canvas.unlink_cell(cell, *direction);
+            }
+        }
+
+        let mut frames = vec![canvas.draw_image(&self.style)];
+        for (step, event) in maze.history().iter().enumerate() {
+            canvas.apply_event(event);
+            if (step + 1).is_multiple_of(self.skip) {
+                frames.push(canvas.draw_image(&self.style));
+            }
+        }
+        if !maze.history().len().is_multiple_of(self.skip) {
+            frames.push(canvas.draw_image(&self.style));
+        }
+        frames
+    }
+
+    /// Return frames colouring each cell in the order it was expanded by the solver
+    pub fn solving<T: Direction, M: Maze<T> + Clone>(
+        &self,
+        maze: &M,
+        distances: &Distances,
+    ) -> Vec<RgbImage> {
+        let mut canvas = maze.clone();
+        canvas._stop_recording();
+        let max = distances.all_cells().values().copied().max();
+        canvas.clear_distances();
+
+        // fix the furthest distance up front so colours do not shift between frames
+        canvas._set_distance(max);
+        let mut frames = vec![canvas.draw_image(&self.style)];
+        for (step, cell) in distances.order().iter().enumerate() {
+            canvas._attributes_mut(cell).distance = distances.distance(*cell).ok();
+            if (step + 1).is_multiple_of(self.skip) {
+                frames.push(canvas.draw_image(&self.style));
+            }
+        }
+        if !distances.order().len().is_multiple_of(self.skip) {
+            frames.push(canvas.draw_image(&self.style));
+        }
+        frames
+    }
+
+    /// Write the frames to a looping GIF file
+    pub fn write(&self, filename: &str, frames: Vec<RgbImage>) -> ImageResult<()> {
+        let file = File::create(filename).map_err(ImageError::IoError)?;
+        let mut encoder = GifEncoder::new(file);
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        encoder.encode_frames(frames.into_iter().map(|image| {
+            Frame::from_parts(
+                DynamicImage::ImageRgb8(image).into_rgba8(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(self.delay, 1),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use crate::solver::dijkstra::Dijkstra;
+    use rand::rngs::mock::StepRng;
+
+    fn recorded() -> Grid {
+        let mut rng = StepRng::new(1, 1);
+        Grid::recorded(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        )
+    }
+
+    #[test]
+    fn check_carving() {
+        let grid = recorded();
+
+        let every = Animation::new(RenderStyle::default(), 1, 50).carving(&grid);
+        let skipped = Animation::new(RenderStyle::default(), 3, 50).carving(&grid);
+
+        // a blank frame followed by one per link
+        assert_eq!(every.len(), 9);
+        // a blank frame, one every 3 links and the final maze
+        assert_eq!(skipped.len(), 4);
+        assert_eq!(
+            every.last(),
+            Some(&grid.draw_image(&RenderStyle::default()))
+        );
+        assert_ne!(every[0], every[8]);
+    }
+
+    #[test]
+    fn check_solving() {
+        let grid = recorded();
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+
+        let frames = Animation::new(RenderStyle::default(), 2, 50).solving(&grid, &distances);
+
+        assert_eq!(frames.len(), 6);
+    }
+}
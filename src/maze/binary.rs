@@ -0,0 +1,288 @@
+use crate::error::Error;
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::Maze;
+use crate::router::internal::NoOp;
+
+use std::io::{self, Read, Write};
+
+/// Start of every encoded maze
+pub const MAGIC: [u8; 4] = *b"MAZE";
+pub const VERSION: u8 = 1;
+/// Most cells a maze read back may have, so a corrupt header cannot ask for gigabytes
+pub const MAX_CELLS: usize = 1 << 24;
+
+/// Flag set in the header when a mask bitmap follows it
+const MASKED: u8 = 0x01;
+/// Wall bits stored for each cell, the north and west walls are the neighbours' walls
+const EAST: u8 = 0x01;
+const SOUTH: u8 = 0x02;
+
+/// Wrap a format error so it can be returned from reading a stream
+fn invalid(reason: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, Error::InvalidFormat(reason))
+}
+
+#[allow(dead_code)]
+impl Grid {
+    /// Write the grid in a compact binary format, small enough to archive mazes by the million
+    ///
+    /// Each maze is a 14 byte header followed by an optional mask bitmap and then the walls:
+    ///
+    /// | bytes            | content                                                         |
+    /// |------------------|-----------------------------------------------------------------|
+    /// | 4                | `MAZE`                                                          |
+    /// | 1                | version                                                         |
+    /// | 1                | flags, bit 0 set if there is a mask                             |
+    /// | 4                | rows, little endian                                             |
+    /// | 4                | columns, little endian                                          |
+    /// | (cells + 7) / 8  | mask, one bit per cell in row order, set for a masked cell      |
+    /// | (cells + 3) / 4  | walls, two bits per cell in row order, east then south          |
+    ///
+    /// Distances and history are not saved. Mazes can be written one after another to the same
+    /// stream and read back with `Mazes`. Mazes of more than `MAX_CELLS` cells are rejected when
+    /// read.
+    pub fn write_binary<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let rows = self.rows();
+        let columns = self.columns();
+        let count = rows as usize * columns as usize;
+        let masked = self.cells().len() < count;
+
+        out.write_all(&MAGIC)?;
+        out.write_all(&[VERSION, if masked { MASKED } else { 0 }])?;
+        out.write_all(&rows.to_le_bytes())?;
+        out.write_all(&columns.to_le_bytes())?;
+
+        let mut mask = vec![0u8; count.div_ceil(8)];
+        let mut walls = vec![0u8; count.div_ceil(4)];
+        for row in 0..rows {
+            for column in 0..columns {
+                let offset = row as usize * columns as usize + column as usize;
+                let mut bits = EAST | SOUTH;
+                match self.cell(row, column) {
+                    Some(cell) => {
+                        let links = self.links(cell);
+                        if links.contains(&Compass::East) {
+                            bits &= !EAST;
+                        }
+                        if links.contains(&Compass::South) {
+                            bits &= !SOUTH;
+                        }
+                    }
+                    None => mask[offset / 8] |= 1 << (offset % 8),
+                }
+                walls[offset / 4] |= bits << (2 * (offset % 4));
+            }
+        }
+        if masked {
+            out.write_all(&mask)?;
+        }
+        out.write_all(&walls)
+    }
+
+    /// Read a grid written by `write_binary`
+    pub fn read_binary<R: Read>(input: &mut R) -> io::Result<Grid> {
+        Grid::read_next(input)?.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))
+    }
+
+    /// Read the next grid, or none if the stream ends cleanly before it starts
+    fn read_next<R: Read>(input: &mut R) -> io::Result<Option<Grid>> {
+        let mut header = [0u8; 14];
+        let mut read = 0;
+        while read < header.len() {
+            match input.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if header[0..4] != MAGIC {
+            return Err(invalid("missing header"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid("unsupported version"));
+        }
+        let flags = header[5];
+        let rows = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+        let columns = u32::from_le_bytes([header[10], header[11], header[12], header[13]]);
+        let count = (rows as usize)
+            .checked_mul(columns as usize)
+            .filter(|count| *count <= MAX_CELLS)
+            .ok_or_else(|| invalid("maze is too large"))?;
+
+        let mask_size = if flags & MASKED != 0 {
+            count.div_ceil(8)
+        } else {
+            0
+        };
+        let mut mask = vec![0u8; mask_size];
+        input.read_exact(&mut mask)?;
+        let mut walls = vec![0u8; count.div_ceil(4)];
+        input.read_exact(&mut walls)?;
+
+        let masked = |offset: usize| {
+            mask.get(offset / 8)
+                .is_some_and(|m| m & (1 << (offset % 8)) != 0)
+        };
+        let mut grid = Grid::grid(
+            rows,
+            columns,
+            |r, c| !masked(r as usize * columns as usize + c as usize),
+            &mut NoOp {},
+        );
+        for row in 0..rows {
+            for column in 0..columns {
+                let offset = row as usize * columns as usize + column as usize;
+                let bits = walls[offset / 4] >> (2 * (offset % 4));
+                let cell = match grid.cell(row, column) {
+                    Some(c) => *c,
+                    None => continue,
+                };
+                // a missing wall with nothing behind it cannot be linked, so is left closed
+                if bits & EAST == 0 {
+                    grid.link_cell(&cell, Compass::East);
+                }
+                if bits & SOUTH == 0 {
+                    grid.link_cell(&cell, Compass::South);
+                }
+            }
+        }
+        Ok(Some(grid))
+    }
+}
+
+/// Iterator over the mazes in a stream written by repeated calls to `Grid::write_binary`
+///
+/// ```
+///     for grid in Mazes::new(BufReader::new(File::open("archive.bin")?)) {
+///         println!("{}", grid?);
+///     }
+/// ```
+pub struct Mazes<R: Read> {
+    input: R,
+    failed: bool,
+}
+
+#[allow(dead_code)]
+impl<R: Read> Mazes<R> {
+    pub fn new(input: R) -> Self {
+        Mazes {
+            input,
+            failed: false,
+        }
+    }
+}
+
+impl<R: Read> Iterator for Mazes<R> {
+    type Item = io::Result<Grid>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = Grid::read_next(&mut self.input);
+        // the position in the stream is lost after an error, so stop there
+        self.failed = next.is_err();
+        next.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    fn maze(mask: bool) -> Grid {
+        let mut rng = StepRng::new(1, 1);
+        Grid::grid(
+            3,
+            5,
+            move |r, c| !mask || r != 1 || c != 2,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        )
+    }
+
+    #[test]
+    fn check_size() {
+        let mut bytes = Vec::new();
+        maze(false)
+            .write_binary(&mut bytes)
+            .expect("Could not write");
+
+        // header plus 15 cells at 4 a byte
+        assert_eq!(bytes.len(), 14 + 4);
+        assert_eq!(&bytes[0..6], b"MAZE\x01\x00");
+
+        let mut bytes = Vec::new();
+        maze(true)
+            .write_binary(&mut bytes)
+            .expect("Could not write");
+
+        assert_eq!(bytes.len(), 14 + 2 + 4);
+    }
+
+    #[test]
+    fn check_round_trip() {
+        for mask in [false, true].iter() {
+            let grid = maze(*mask);
+            let mut bytes = Vec::new();
+            grid.write_binary(&mut bytes).expect("Could not write");
+
+            let read = Grid::read_binary(&mut bytes.as_slice()).expect("Could not read");
+
+            assert_eq!(read.to_string(), grid.to_string());
+        }
+    }
+
+    #[test]
+    fn check_stream() {
+        let mut bytes = Vec::new();
+        maze(false)
+            .write_binary(&mut bytes)
+            .expect("Could not write");
+        maze(true)
+            .write_binary(&mut bytes)
+            .expect("Could not write");
+        Grid::square(2)
+            .write_binary(&mut bytes)
+            .expect("Could not write");
+
+        let grids: Vec<Grid> = Mazes::new(bytes.as_slice())
+            .collect::<io::Result<_>>()
+            .expect("Could not read");
+
+        assert_eq!(grids.len(), 3);
+        assert_eq!(grids[1].to_string(), maze(true).to_string());
+        assert_eq!(grids[2].to_string(), Grid::square(2).to_string());
+    }
+
+    #[test]
+    fn check_invalid() {
+        let mut bytes = Vec::new();
+        maze(false)
+            .write_binary(&mut bytes)
+            .expect("Could not write");
+
+        let error = Grid::read_binary(&mut &bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        bytes[0] = b'X';
+        let error = Grid::read_binary(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "Invalid maze format: missing header");
+
+        let mut results = Mazes::new(bytes.as_slice());
+        assert!(results.next().expect("Missing result").is_err());
+        assert!(results.next().is_none());
+
+        // a header asking for far more cells than allowed, with no data after it
+        let mut header = b"MAZE\x01\x00".to_vec();
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        let error = Grid::read_binary(&mut header.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "Invalid maze format: maze is too large");
+    }
+}
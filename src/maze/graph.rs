@@ -0,0 +1,217 @@
+use crate::maze::{Cell, Direction, Maze};
+use crate::util::svg::escape;
+
+use std::fmt::Write;
+use std::marker::PhantomData;
+
+/// Exports a maze as a graph, with cells as nodes and links as edges
+///
+/// Applied distances are added to the nodes. Walls between neighbouring cells can be added
+/// as dashed edges in DOT and edges with `linked` false in GraphML.
+/// ```
+///     fs::write("maze.dot", Graph::new(&grid).walls(true).dot())?;
+/// ```
+pub struct Graph<'a, T: Direction, M: Maze<T>> {
+    maze: &'a M,
+    walls: bool,
+    name: String,
+    direction: PhantomData<T>,
+}
+
+#[allow(dead_code)]
+impl<'a, T: Direction, M: Maze<T>> Graph<'a, T, M> {
+    pub fn new(maze: &'a M) -> Self {
+        Graph {
+            maze,
+            walls: false,
+            name: "maze".to_string(),
+            direction: PhantomData,
+        }
+    }
+
+    /// Include an edge for each wall between neighbouring cells
+    pub fn walls(mut self, walls: bool) -> Self {
+        self.walls = walls;
+        self
+    }
+
+    /// Set the name of the graph
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    fn id(cell: &Cell) -> String {
+        format!("r{}c{}", cell.row(), cell.column())
+    }
+
+    /// Return each pair of neighbouring cells once, in row order, and whether they are linked
+    fn edges(&self) -> Vec<(Cell, Cell, bool)> {
+        let mut edges = Vec::new();
+        for cell in self.maze.cells() {
+            let links = self.maze.links(cell);
+            let mut neighbours: Vec<(&T, &Cell)> = self
+                .maze
+                .neighbours(cell)
+                .iter()
+                .filter(|(_, n)| n.coords() > cell.coords())
+                .collect();
+            neighbours.sort_by_key(|(_, n)| n.coords());
+            for (direction, neighbour) in neighbours {
+                let linked = links.contains(direction);
+                if linked || self.walls {
+                    edges.push((*cell, *neighbour, linked));
+                }
+            }
+        }
+        edges
+    }
+
+    /// Return the graph in Graphviz DOT format, laid out to match the maze
+    pub fn dot(&self) -> String {
+        let mut s = String::new();
+        // escape backslashes first, or the one added before a quote would be doubled
+        let name = self.name.replace('\\', "\\\\").replace('"', "\\\"");
+        let _ = writeln!(s, "graph \"{}\" {{", name);
+        let _ = writeln!(s, "  node [shape=box];");
+        for cell in self.maze.cells() {
+            let mut label = format!("{},{}", cell.row(), cell.column());
+            if let Some(distance) = self.maze._attributes(cell).distance() {
+                let _ = write!(label, "\\n{}", distance);
+            }
+            let _ = writeln!(
+                s,
+                "  {} [label=\"{}\", pos=\"{},{}!\"];",
+                Graph::<T, M>::id(cell),
+                label,
+                cell.column(),
+                -(cell.row() as i64)
+            );
+        }
+        for (from, to, linked) in self.edges() {
+            let _ = writeln!(
+                s,
+                "  {} -- {}{};",
+                Graph::<T, M>::id(&from),
+                Graph::<T, M>::id(&to),
+                if linked { "" } else { " [style=dashed]" }
+            );
+        }
+        s.push_str("}\n");
+        s
+    }
+
+    /// Return the graph in GraphML format
+    pub fn graphml(&self) -> String {
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        s.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (id, target, name, kind) in [
+            ("row", "node", "row", "int"),
+            ("column", "node", "column", "int"),
+            ("distance", "node", "distance", "int"),
+            ("linked", "edge", "linked", "boolean"),
+        ]
+        .iter()
+        {
+            let _ = writeln!(
+                s,
+                "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>",
+                id, target, name, kind
+            );
+        }
+        let _ = writeln!(
+            s,
+            "  <graph id=\"{}\" edgedefault=\"undirected\">",
+            escape(&self.name)
+        );
+        for cell in self.maze.cells() {
+            let _ = write!(
+                s,
+                "    <node id=\"{}\"><data key=\"row\">{}</data><data key=\"column\">{}</data>",
+                Graph::<T, M>::id(cell),
+                cell.row(),
+                cell.column()
+            );
+            if let Some(distance) = self.maze._attributes(cell).distance() {
+                let _ = write!(s, "<data key=\"distance\">{}</data>", distance);
+            }
+            s.push_str("</node>\n");
+        }
+        for (from, to, linked) in self.edges() {
+            let _ = writeln!(
+                s,
+                "    <edge source=\"{}\" target=\"{}\"><data key=\"linked\">{}</data></edge>",
+                Graph::<T, M>::id(&from),
+                Graph::<T, M>::id(&to),
+                linked
+            );
+        }
+        s.push_str("  </graph>\n</graphml>\n");
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::internal::NoOp;
+    use crate::solver::dijkstra::Dijkstra;
+
+    fn maze() -> Grid {
+        let mut grid = Grid::grid(2, 2, |r, c| r != 1 || c != 1, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        grid
+    }
+
+    #[test]
+    fn check_dot() {
+        assert_eq!(
+            Graph::new(&maze()).dot(),
+            r#"graph "maze" {
+  node [shape=box];
+  r0c0 [label="0,0", pos="0,0!"];
+  r0c1 [label="0,1", pos="1,0!"];
+  r1c0 [label="1,0", pos="0,-1!"];
+  r0c0 -- r0c1;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn check_dot_name() {
+        let dot = Graph::new(&maze()).name(r#"say "hi" \"#).dot();
+
+        assert!(dot.starts_with(r#"graph "say \"hi\" \\" {"#));
+    }
+
+    #[test]
+    fn check_dot_walls() {
+        let mut grid = maze();
+        grid.apply_distances(Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze"));
+
+        let dot = Graph::new(&grid).walls(true).dot();
+
+        assert!(dot.contains(r#"r0c1 [label="0,1\n1", pos="1,0!"];"#));
+        assert!(dot.contains("  r0c0 -- r0c1;\n  r0c0 -- r1c0 [style=dashed];\n"));
+        assert_eq!(dot.matches("--").count(), 2);
+    }
+
+    #[test]
+    fn check_graphml() {
+        let graphml = Graph::new(&maze()).name("a<b").walls(true).graphml();
+
+        assert!(graphml.contains(r#"<graph id="a&lt;b" edgedefault="undirected">"#));
+        assert_eq!(graphml.matches("<node ").count(), 3);
+        assert!(graphml.contains(
+            r#"<edge source="r0c0" target="r0c1"><data key="linked">true</data></edge>"#
+        ));
+        assert!(graphml.contains(
+            r#"<edge source="r0c0" target="r1c0"><data key="linked">false</data></edge>"#
+        ));
+        assert!(!graphml.contains(r#"key="distance">"#));
+    }
+}
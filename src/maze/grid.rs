@@ -1,8 +1,14 @@
+use crate::error::{Error, Result};
 use crate::maze::internal::{Attributes, MazeAccessor};
-use crate::maze::{Cell, Direction, Maze};
+#[cfg(feature = "serde")]
+use crate::maze::json::GridData;
+use crate::maze::style::RenderStyle;
+use crate::maze::{Cell, Direction, Event, Maze};
 use crate::router::internal::NoOp;
 use crate::router::Router;
+use crate::solver::Distances;
 use crate::util;
+use crate::util::svg::{Document, Point};
 
 use image::{Rgb, RgbImage};
 use imageproc::{drawing, rect};
@@ -10,8 +16,14 @@ use std::char;
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::Hash;
+use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Compass {
     North,
     East,
@@ -33,6 +45,24 @@ impl Direction for Compass {
         }
     }
 
+    fn clockwise(&self) -> Compass {
+        match self {
+            Compass::North => Compass::East,
+            Compass::East => Compass::South,
+            Compass::South => Compass::West,
+            Compass::West => Compass::North,
+        }
+    }
+
+    fn anticlockwise(&self) -> Compass {
+        match self {
+            Compass::North => Compass::West,
+            Compass::East => Compass::North,
+            Compass::South => Compass::East,
+            Compass::West => Compass::South,
+        }
+    }
+
     fn neighbour(&self, row: u32, column: u32) -> (u32, u32) {
         match self {
             Compass::North => (row - 1, column),
@@ -67,13 +97,22 @@ impl Direction for Compass {
     }
 }
 
-#[derive(Debug)]
+/// Axis aligned rectangle as x, y, width and height
+type Area = (f32, f32, f32, f32);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "GridData", try_from = "GridData")
+)]
 pub struct Grid {
     rows: u32,
     columns: u32,
     cells: Vec<Option<Cell>>,
     attributes: HashMap<Cell, Attributes<Compass>>,
     max_distance: Option<u32>,
+    history: Option<Vec<Event<Compass>>>,
 }
 
 #[allow(dead_code)]
@@ -98,12 +137,41 @@ impl Grid {
     /// );
     /// ```
     ///
+    #[allow(clippy::self_named_constructors)]
     pub fn grid<F>(
         rows: u32,
         columns: u32,
         allowed: F,
         router: &mut dyn Router<Compass, Grid>,
     ) -> Self
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        Grid::build(rows, columns, allowed, router, None)
+    }
+
+    /// Build a new grid instance, recording every link and unlink made while carving.
+    ///
+    /// Takes the same arguments as `grid`, the events are available from `history`.
+    pub fn recorded<F>(
+        rows: u32,
+        columns: u32,
+        allowed: F,
+        router: &mut dyn Router<Compass, Grid>,
+    ) -> Self
+    where
+        F: Fn(u32, u32) -> bool,
+    {
+        Grid::build(rows, columns, allowed, router, Some(Vec::new()))
+    }
+
+    fn build<F>(
+        rows: u32,
+        columns: u32,
+        allowed: F,
+        router: &mut dyn Router<Compass, Grid>,
+        history: Option<Vec<Event<Compass>>>,
+    ) -> Self
     where
         F: Fn(u32, u32) -> bool,
     {
@@ -117,6 +185,7 @@ impl Grid {
             cells,
             attributes,
             max_distance: None,
+            history,
         };
 
         router.carve(&mut grid, c);
@@ -127,6 +196,99 @@ impl Grid {
     pub fn square(size: u32) -> Self {
         Grid::grid(size, size, Grid::ALLOW_ALL, &mut NoOp {})
     }
+
+    /// Return the inner square of an inset cell plus a corridor out to each linked edge
+    ///
+    /// # Arguments
+    /// * `cell` - the cell to draw
+    /// * `x`, `y` - top left corner of the cell
+    /// * `size` - width and height of the cell
+    /// * `inset` - distance from the cell edge to the corridor
+    fn inset_areas(&self, cell: &Cell, x: f32, y: f32, size: f32, inset: f32) -> Vec<Area> {
+        let inner = size - 2.0 * inset;
+        let mut areas = vec![(x + inset, y + inset, inner, inner)];
+        let links = self.links(cell);
+
+        if links.contains(&Compass::North) {
+            areas.push((x + inset, y, inner, inset));
+        }
+        if links.contains(&Compass::East) {
+            areas.push((x + size - inset, y + inset, inset, inner));
+        }
+        if links.contains(&Compass::South) {
+            areas.push((x + inset, y + size - inset, inner, inset));
+        }
+        if links.contains(&Compass::West) {
+            areas.push((x, y + inset, inset, inner));
+        }
+        areas
+    }
+
+    /// Draw the maze as corridors, outlining every area in the wall colour before filling them
+    fn draw_inset_image(&self, style: &RenderStyle) -> RgbImage {
+        let (size, wall) = style.pixel_sizes();
+        let (size, wall) = (size as f32, wall as f32);
+        let margin = style.margin as f32;
+        let (width, height) = style.image_size(self.rows, self.columns);
+        let mut image: RgbImage = image::ImageBuffer::from_pixel(width, height, style.background);
+        let mut fills = Vec::new();
+
+        // masked cells first, so the outlines of the corridors beside them are drawn on top
+        for (offset, cell) in self.cells.iter().enumerate() {
+            if cell.is_none() {
+                let x = margin + size * (offset as u32 % self.columns) as f32;
+                let y = margin + size * (offset as u32 / self.columns) as f32;
+                drawing::draw_filled_rect_mut(
+                    &mut image,
+                    pixel_rect(x, y, size, size),
+                    style.masked,
+                );
+            }
+        }
+        for c in self.cells.iter().flatten() {
+            let x = margin + size * c.column() as f32;
+            let y = margin + size * c.row() as f32;
+            let colour = self.cell_colour(c, style);
+
+            for (ax, ay, aw, ah) in self.inset_areas(c, x, y, size, style.inset_size()) {
+                drawing::draw_filled_rect_mut(
+                    &mut image,
+                    pixel_rect(ax - wall, ay - wall, aw + 2.0 * wall, ah + 2.0 * wall),
+                    style.wall,
+                );
+                fills.push((pixel_rect(ax, ay, aw, ah), colour));
+            }
+        }
+        for (area, colour) in fills {
+            drawing::draw_filled_rect_mut(&mut image, area, colour);
+        }
+        image
+    }
+
+    /// Return the fill colour of a cell from its applied distance, owner and fill state
+    pub(super) fn cell_colour(&self, cell: &Cell, style: &RenderStyle) -> Rgb<u8> {
+        let attributes = self._attributes(cell);
+
+        if attributes.filled() {
+            style.filled
+        } else if let Some(distance) = attributes.distance() {
+            // every cell is a source when the furthest distance is zero, avoid dividing by it
+            let ratio = match self.max_distance.expect("Max distance not set") {
+                0 => 0.0,
+                max => distance as f32 / max as f32,
+            };
+            match attributes.owner() {
+                Some(owner) => util::image::gradient_colour(
+                    style.gradient.colour(0.0),
+                    util::image::owner_colour(owner),
+                    ratio,
+                ),
+                None => style.gradient.colour(ratio),
+            }
+        } else {
+            style.cell
+        }
+    }
 }
 
 impl MazeAccessor<Compass> for Grid {
@@ -149,6 +311,23 @@ impl MazeAccessor<Compass> for Grid {
             .get_mut(cell)
             .unwrap_or_else(|| panic!("Missing attribute for {:?}", cell))
     }
+
+    fn _history(&self) -> &[Event<Compass>] {
+        match &self.history {
+            Some(history) => history,
+            None => &[],
+        }
+    }
+
+    fn _record(&mut self, event: Event<Compass>) {
+        if let Some(history) = &mut self.history {
+            history.push(event);
+        }
+    }
+
+    fn _stop_recording(&mut self) {
+        self.history = None;
+    }
 }
 
 impl Maze<Compass> for Grid {
@@ -159,80 +338,134 @@ impl Maze<Compass> for Grid {
         self.columns
     }
 
-    fn draw_image(&self) -> image::RgbImage {
-        const WHITE: Rgb<u8> = Rgb([255u8, 255u8, 255u8]);
-        const BLACK: Rgb<u8> = Rgb([0u8, 0u8, 0u8]);
-        const GREY: Rgb<u8> = Rgb([128u8, 128u8, 128u8]);
-        const BLUE: Rgb<u8> = Rgb([0u8, 0u8, 255u8]);
-        let size = 10;
+    fn draw_image(&self, style: &RenderStyle) -> image::RgbImage {
+        if style.inset > 0.0 {
+            return self.draw_inset_image(style);
+        }
+        let (size, wall) = style.pixel_sizes();
+        let margin = style.margin;
+        let (width, height) = style.image_size(self.rows, self.columns);
 
-        // Create a new ImgBuf with width and height and grey background
-        let mut image: RgbImage =
-            image::ImageBuffer::from_pixel(size * (self.columns + 2), size * (self.rows + 2), GREY);
+        // Create a new ImgBuf with width and height and background colour
+        let mut image: RgbImage = image::ImageBuffer::from_pixel(width, height, style.background);
 
-        // fill in the maze with white and draw a black outline
+        // fill in the maze with the wall colour, leaving the outline
         drawing::draw_filled_rect_mut(
             &mut image,
-            rect::Rect::at((size - 1) as i32, (size - 1) as i32)
-                .of_size((size * self.columns) + 1, (size * self.rows) + 1),
-            BLACK,
+            // a margin thinner than the wall clips the outline at the image edge
+            rect::Rect::at(margin as i32 - wall as i32, margin as i32 - wall as i32)
+                .of_size((size * self.columns) + wall, (size * self.rows) + wall),
+            style.wall,
         );
 
-        for cell in &self.cells {
-            if let Some(c) = cell {
-                let colour = if let Some(distance) = self._attributes(c).distance() {
-                    util::image::gradient_colour(
-                        WHITE,
-                        BLUE,
-                        distance as f32 / self.max_distance.expect("Max distance not set") as f32,
-                    )
-                } else {
-                    WHITE
-                };
-
-                // cut our valid cells
+        for (offset, cell) in self.cells.iter().enumerate() {
+            let x = margin + size * (offset as u32 % self.columns);
+            let y = margin + size * (offset as u32 / self.columns);
+            let colour = match cell {
+                Some(c) => self.cell_colour(c, style),
+                None => style.masked,
+            };
+
+            // cut out the cell
+            drawing::draw_filled_rect_mut(
+                &mut image,
+                rect::Rect::at(x as i32, y as i32).of_size(size - wall, size - wall),
+                colour,
+            );
+            // cut out wall from top-right to bottom-right
+            if self.has_link(cell, Compass::East) {
                 drawing::draw_filled_rect_mut(
                     &mut image,
-                    rect::Rect::at(
-                        (size * (c.column() + 1)) as i32,
-                        (size * (c.row() + 1)) as i32,
-                    )
-                    .of_size(size - 1, size - 1),
+                    rect::Rect::at((x + size - wall) as i32, y as i32).of_size(wall, size - wall),
                     colour,
                 );
-                // cut out wall from top-right to bottom-right
-                if self.has_link(&cell, Compass::East) {
-                    drawing::draw_line_segment_mut(
-                        &mut image,
-                        (
-                            ((size * (c.column() + 2)) - 1) as f32,
-                            (size * (c.row() + 1)) as f32,
-                        ),
-                        (
-                            ((size * (c.column() + 2)) - 1) as f32,
-                            ((size * (c.row() + 2)) - 2) as f32,
-                        ),
-                        colour,
-                    );
+            }
+            // cut out wall from bottom-left to bottom-right
+            if self.has_link(cell, Compass::South) {
+                drawing::draw_filled_rect_mut(
+                    &mut image,
+                    rect::Rect::at(x as i32, (y + size - wall) as i32).of_size(size - wall, wall),
+                    colour,
+                );
+            }
+        }
+        image
+    }
+
+    fn draw_svg(&self, style: &RenderStyle, coloured: bool, solution: Option<&[Cell]>) -> String {
+        let size = style.cell_size as f32;
+        let margin = style.margin as f32;
+        let (width, height) = style.image_size(self.rows, self.columns);
+        let mut document = Document::new(width, height, style.antialias);
+        let wall = style.wall_thickness as f32;
+        let mut walls = Vec::new();
+        let mut outlines = Vec::new();
+        let mut fills = Vec::new();
+
+        document.rect(0.0, 0.0, width as f32, height as f32, style.background);
+        for (offset, cell) in self.cells.iter().enumerate() {
+            let x = margin + size * (offset as u32 % self.columns) as f32;
+            let y = margin + size * (offset as u32 / self.columns) as f32;
+            let c = match cell {
+                Some(c) => c,
+                None => {
+                    document.rect(x, y, size, size, style.masked);
+                    continue;
                 }
-                // cut out wall from bottom-left to bottom-right
-                if self.has_link(&cell, Compass::South) {
-                    drawing::draw_line_segment_mut(
-                        &mut image,
-                        (
-                            (size * (c.column() + 1)) as f32,
-                            ((size * (c.row() + 2)) - 1) as f32,
-                        ),
-                        (
-                            ((size * (c.column() + 2)) - 2) as f32,
-                            ((size * (c.row() + 2)) - 1) as f32,
-                        ),
-                        colour,
-                    );
+            };
+            let colour = if coloured {
+                self.cell_colour(c, style)
+            } else {
+                style.cell
+            };
+
+            if style.inset > 0.0 {
+                for (ax, ay, aw, ah) in self.inset_areas(c, x, y, size, style.inset_size()) {
+                    outlines.push((ax - wall, ay - wall, aw + 2.0 * wall, ah + 2.0 * wall));
+                    fills.push(((ax, ay, aw, ah), colour));
                 }
+                continue;
+            }
+
+            let neighbours = self.neighbours(c);
+            document.rect(x, y, size, size, colour);
+            // each cell draws its north and west walls, and the east and south walls only on
+            // the edge of the maze or next to a masked cell
+            if !self.has_link(cell, Compass::North) {
+                walls.push(((x, y), (x + size, y)));
+            }
+            if !self.has_link(cell, Compass::West) {
+                walls.push(((x, y), (x, y + size)));
+            }
+            if !neighbours.contains_key(&Compass::East) {
+                walls.push(((x + size, y), (x + size, y + size)));
+            }
+            if !neighbours.contains_key(&Compass::South) {
+                walls.push(((x, y + size), (x + size, y + size)));
             }
         }
-        image
+        // inset corridors are outlined by drawing them larger in the wall colour underneath
+        for (x, y, w, h) in outlines {
+            document.rect(x, y, w, h, style.wall);
+        }
+        for ((x, y, w, h), colour) in fills {
+            document.rect(x, y, w, h, colour);
+        }
+        document.lines(&walls, style.wall, wall);
+
+        if let Some(path) = solution {
+            let points: Vec<Point> = path
+                .iter()
+                .map(|c| {
+                    (
+                        margin + size * (c.column() as f32 + 0.5),
+                        margin + size * (c.row() as f32 + 0.5),
+                    )
+                })
+                .collect();
+            document.polyline(&points, style.solution, size / 4.0);
+        }
+        document.finish()
     }
 
     fn write_row<F1, F2>(&self, s: &mut String, scale: u32, row: &[Option<Cell>], f1: F1, f2: F2)
@@ -252,6 +485,14 @@ impl Maze<Compass> for Grid {
     }
 }
 
+/// Convert an area to the nearest whole pixel rectangle
+fn pixel_rect(x: f32, y: f32, width: f32, height: f32) -> rect::Rect {
+    rect::Rect::at(x.round() as i32, y.round() as i32).of_size(
+        width.round().max(1.0) as u32,
+        height.round().max(1.0) as u32,
+    )
+}
+
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         const VDIV: char = '|';
@@ -319,9 +560,81 @@ impl fmt::Display for Grid {
     }
 }
 
+/// Read a grid back from its `Display` form, so hand drawn mazes can be used as fixtures
+///
+/// Each cell is three characters wide, a space in place of a wall is a link and `█` marks a
+/// masked cell. Distances written as lower case base 36 digits are applied as distances, any
+/// other character in a cell is an error. Blank lines before and after the maze and Windows
+/// line endings are ignored.
+/// ```
+///     let grid: Grid = "+---+---+\n|       |\n+---+---+\n".parse()?;
+/// ```
+impl FromStr for Grid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines: Vec<Vec<char>> = s
+            .lines()
+            .map(|l| l.trim_end_matches('\r').trim_end().chars().collect())
+            .skip_while(|l: &Vec<char>| l.is_empty())
+            .collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        let width = lines.first().map_or(0, |l| l.len());
+        if lines.len() < 3 || lines.len().is_multiple_of(2) || width < 5 || width % 4 != 1 {
+            return Err(Error::InvalidFormat("maze is not a whole number of cells"));
+        }
+        if lines.iter().any(|l| l.len() != width) {
+            return Err(Error::InvalidFormat("lines are not all the same length"));
+        }
+        let rows = (lines.len() / 2) as u32;
+        let columns = (width / 4) as u32;
+        let body = |row: u32, column: u32| lines[2 * row as usize + 1][4 * column as usize + 2];
+
+        let mut grid = Grid::grid(rows, columns, |r, c| body(r, c) != '█', &mut NoOp {});
+        let mut distances = HashMap::new();
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let cell = match grid.cell(row, column) {
+                    Some(c) => *c,
+                    None => continue,
+                };
+                match body(row, column) {
+                    ' ' => {}
+                    // only the digits `Display` writes, `to_digit` would read upper case too
+                    ch @ ('0'..='9' | 'a'..='z') => {
+                        distances.insert(cell, ch.to_digit(36).expect("Invalid digit"));
+                    }
+                    _ => return Err(Error::InvalidFormat("unknown character in a cell")),
+                }
+                let east = lines[2 * row as usize + 1][4 * (column as usize + 1)] == ' ';
+                let south = lines[2 * row as usize + 2][4 * column as usize + 2] == ' ';
+                // openings in the outer wall have nowhere to lead so are left as walls
+                if east && column + 1 < columns && grid.link_cell(&cell, Compass::East).is_none()
+                    || south && row + 1 < rows && grid.link_cell(&cell, Compass::South).is_none()
+                {
+                    return Err(Error::InvalidFormat("passage into a masked cell"));
+                }
+            }
+        }
+        if !distances.is_empty() {
+            grid.apply_distances(Distances::new(distances));
+        }
+        Ok(grid)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::router::sidewinder::SideWinder;
+    use crate::solver::dijkstra::Dijkstra;
+    use crate::solver::internal::SimpleSolver;
+    use crate::solver::Solver;
+    use crate::util::colourmap::ColourMap;
+    use rand::rngs::mock::StepRng;
 
     #[test]
     fn check_direction_points() {
@@ -335,6 +648,14 @@ mod tests {
         assert_eq!(points.next(), None);
     }
 
+    #[test]
+    fn check_direction_rotate() {
+        assert_eq!(Compass::North.clockwise(), Compass::East);
+        assert_eq!(Compass::West.clockwise(), Compass::North);
+        assert_eq!(Compass::North.anticlockwise(), Compass::West);
+        assert_eq!(Compass::East.anticlockwise(), Compass::North);
+    }
+
     #[test]
     fn check_direction_neighbour() {
         assert_eq!(Compass::North.neighbour(1, 1), (0, 1));
@@ -410,8 +731,8 @@ mod tests {
     fn check_bounds() {
         let grid = Grid::square(3);
 
-        assert!(matches!(grid.cell(0, 3), None));
-        assert!(matches!(grid.cell(4, 0), None));
+        assert!(grid.cell(0, 3).is_none());
+        assert!(grid.cell(4, 0).is_none());
     }
 
     #[test]
@@ -420,8 +741,8 @@ mod tests {
         let cell = grid.cell(0, 0).expect("Missing Cell 0,0");
         let neighbours = grid.neighbours(cell);
 
-        assert!(matches!(neighbours.get(&Compass::North), None));
-        assert!(matches!(neighbours.get(&Compass::West), None));
+        assert!(neighbours.get(&Compass::North).is_none());
+        assert!(neighbours.get(&Compass::West).is_none());
         assert_eq!(neighbours.get(&Compass::South), grid.cell(1, 0));
         assert_eq!(neighbours.get(&Compass::East), grid.cell(0, 1));
     }
@@ -432,10 +753,10 @@ mod tests {
         let cell = grid.cell(0, 2).expect("Missing Cell");
         let neighbours = grid.neighbours(cell);
 
-        assert!(matches!(neighbours.get(&Compass::North), None));
+        assert!(neighbours.get(&Compass::North).is_none());
         assert_eq!(neighbours.get(&Compass::West), grid.cell(0, 1));
         assert_eq!(neighbours.get(&Compass::South), grid.cell(1, 2));
-        assert!(matches!(neighbours.get(&Compass::East), None));
+        assert!(neighbours.get(&Compass::East).is_none());
     }
     #[test]
     fn check_neighbour_center() {
@@ -456,8 +777,8 @@ mod tests {
         let neighbours = grid.neighbours(cell);
 
         assert_eq!(neighbours.get(&Compass::North), grid.cell(1, 0));
-        assert!(matches!(neighbours.get(&Compass::West), None));
-        assert!(matches!(neighbours.get(&Compass::South), None));
+        assert!(neighbours.get(&Compass::West).is_none());
+        assert!(neighbours.get(&Compass::South).is_none());
         assert_eq!(neighbours.get(&Compass::East), grid.cell(2, 1));
     }
 
@@ -469,8 +790,8 @@ mod tests {
 
         assert_eq!(neighbours.get(&Compass::North), grid.cell(1, 2));
         assert_eq!(neighbours.get(&Compass::West), grid.cell(2, 1));
-        assert!(matches!(neighbours.get(&Compass::South), None));
-        assert!(matches!(neighbours.get(&Compass::East), None));
+        assert!(neighbours.get(&Compass::South).is_none());
+        assert!(neighbours.get(&Compass::East).is_none());
     }
 
     #[test]
@@ -519,8 +840,55 @@ mod tests {
 
         assert_eq!(grid.cells.len(), 6);
         assert_eq!(grid.cells().len(), 3);
-        assert!(matches!(grid.cell(0, 0), None));
-        assert!(matches!(grid.cell(0, 1), Some(_)));
+        assert!(grid.cell(0, 0).is_none());
+        assert!(grid.cell(0, 1).is_some());
+    }
+
+    #[test]
+    fn check_history() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::recorded(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+
+        assert_eq!(grid.history().len(), 8);
+        assert_eq!(grid.history()[0], Event::Link(cell, Compass::East));
+        assert!(Grid::square(2).history().is_empty());
+    }
+
+    #[test]
+    fn check_history_unlink() {
+        let mut grid = Grid::recorded(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+
+        grid.link_cell(&cell, Compass::East);
+        grid.link_cell(&cell, Compass::West);
+        grid.unlink_cell(&cell, Compass::East);
+
+        assert_eq!(
+            grid.history(),
+            &[
+                Event::Link(cell, Compass::East),
+                Event::Unlink(cell, Compass::East)
+            ]
+        );
+    }
+
+    #[test]
+    fn check_stop_recording() {
+        let grid = Grid::recorded(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        let mut copy = grid.clone();
+
+        copy._stop_recording();
+        copy.link_cell(&cell, Compass::East);
+
+        assert!(copy.history().is_empty());
+        assert!(grid.history().is_empty());
     }
 
     #[test]
@@ -531,7 +899,7 @@ mod tests {
         let cell_11 = *grid.cell(1, 1).expect("Missing Cell 1,1");
 
         // add link from 1,1 North
-        assert!(matches!(grid.link_cell(&cell_11, Compass::North), Some(_)));
+        assert!(grid.link_cell(&cell_11, Compass::North).is_some());
 
         assert!(grid.links(&cell_01).contains(&Compass::South));
         assert!(grid.links(&cell_11).contains(&Compass::North));
@@ -544,7 +912,7 @@ mod tests {
         let cell_01 = *grid.cell(0, 1).expect("Missing Cell 0,1");
 
         // add link from 1,1 North
-        assert!(matches!(grid.link_cell(&cell_01, Compass::North), None));
+        assert!(grid.link_cell(&cell_01, Compass::North).is_none());
         assert!(grid.links(&cell_01).is_empty());
     }
 
@@ -589,6 +957,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_parse() {
+        let text = r#"
++---+---+---+
+|           |
++---+---+   +
+|███|       |
++---+   +---+
+|           |
++---+---+---+
+"#;
+
+        let grid: Grid = text.parse().expect("Could not parse grid");
+
+        assert_eq!(grid.size(), (3, 3));
+        assert!(grid.cell(1, 0).is_none());
+        assert_eq!(format!("\n{}", grid), text);
+    }
+
+    #[test]
+    fn check_parse_round_trip() {
+        let mut rng = StepRng::new(1, 1);
+        let mut grid = Grid::grid(
+            4,
+            5,
+            |r, c| r != 2 || c != 2,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+        grid.apply_distances(
+            SimpleSolver {}
+                .solve(&grid, (0, 0))
+                .expect("Could not solve"),
+        );
+
+        let parsed: Grid = grid.to_string().parse().expect("Could not parse grid");
+
+        assert_eq!(parsed.to_string(), grid.to_string());
+        let cell = *parsed.cell(3, 4).expect("Missing Cell 3,4");
+        assert_eq!(parsed._attributes(&cell).distance(), Some(7));
+    }
+
+    #[test]
+    fn check_parse_invalid() {
+        assert_eq!(
+            "+---+\n|   |\n".parse::<Grid>().unwrap_err(),
+            Error::InvalidFormat("maze is not a whole number of cells")
+        );
+        assert_eq!(
+            "+---+\n|   |\n+---+---+\n".parse::<Grid>().unwrap_err(),
+            Error::InvalidFormat("lines are not all the same length")
+        );
+        assert_eq!(
+            "+---+---+\n|    ███|\n+---+---+\n"
+                .parse::<Grid>()
+                .unwrap_err(),
+            Error::InvalidFormat("passage into a masked cell")
+        );
+        for text in ["+---+\n| S |\n+---+\n", "+---+\n| # |\n+---+\n"].iter() {
+            assert_eq!(
+                text.parse::<Grid>().unwrap_err(),
+                Error::InvalidFormat("unknown character in a cell"),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn check_parse_windows_line_endings() {
+        let grid: Grid = "\r\n+---+---+\r\n| 0   1 |\r\n+---+---+\r\n\r\n"
+            .parse()
+            .expect("Could not parse grid");
+        let cell = *grid.cell(0, 1).expect("Missing Cell 0,1");
+
+        assert_eq!(grid.size(), (1, 2));
+        assert_eq!(grid._attributes(&cell).distance(), Some(1));
+        assert_eq!(grid.to_string(), "+---+---+\n| 0   1 |\n+---+---+\n");
+    }
+
     #[test]
     fn check_string_linked() {
         let mut grid = Grid::square(2);
@@ -611,6 +1058,178 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_draw_regions() {
+        let mut grid = Grid::square(3);
+        for cell in grid.cells().into_iter().copied().collect::<Vec<Cell>>() {
+            grid.link_cell(&cell, Compass::East);
+            grid.link_cell(&cell, Compass::South);
+        }
+        let regions = Dijkstra::solve_many(&grid, &[(0, 0), (2, 2)]).expect("Could not solve maze");
+        grid.apply_regions(regions);
+
+        let image = grid.draw_image(&RenderStyle::default());
+
+        assert_eq!(image.get_pixel(15, 15), &Rgb([255u8, 255u8, 255u8])); // source = white
+        assert_eq!(image.get_pixel(25, 15), &Rgb([127u8, 127u8, 255u8])); // first = blue
+        assert_eq!(image.get_pixel(35, 25), &Rgb([255u8, 127u8, 127u8])); // second = red
+    }
+
+    #[test]
+    fn check_regions_colour_single_cell() {
+        let mut grid = Grid::square(1);
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        let style = RenderStyle::default();
+        grid.apply_regions(Dijkstra::solve_many(&grid, &[(0, 0)]).expect("Could not solve maze"));
+
+        assert_eq!(grid.cell_colour(&cell, &style), style.gradient.colour(0.0));
+    }
+
+    #[test]
+    fn check_draw_svg() {
+        let mut grid = Grid::grid(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        let path: Vec<Cell> = grid.cells().into_iter().copied().collect();
+
+        let svg = grid.draw_svg(&RenderStyle::default(), false, Some(&path));
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="0 0 40 30""#));
+        assert!(svg.contains(
+            r#"d="M10 10L20 10M10 10L10 20M10 20L20 20M20 10L30 10M30 10L30 20M20 20L30 20""#
+        ));
+        assert!(svg.contains(r#"points="15,15 25,15""#));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="40" height="30" fill="#808080"/>"##));
+    }
+
+    #[test]
+    fn check_draw_svg_coloured() {
+        let grid = Grid::grid(1, 2, |_, c| c == 1, &mut NoOp {});
+
+        let svg = grid.draw_svg(&RenderStyle::default(), true, None);
+
+        assert!(svg.contains(r##"<rect x="10" y="10" width="10" height="10" fill="#000000"/>"##));
+        assert!(svg.contains(r##"<rect x="20" y="10" width="10" height="10" fill="#ffffff"/>"##));
+        assert!(!svg.contains("<polyline"));
+    }
+
+    #[test]
+    fn check_draw_style() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        let style = RenderStyle {
+            margin: 4,
+            ..RenderStyle::high_contrast()
+        };
+
+        let image = grid.draw_image(&style);
+
+        assert_eq!(image.width(), 40);
+        assert_eq!(image.get_pixel(0, 0), &style.background);
+        assert_eq!(image.get_pixel(2, 2), &style.wall); // outline
+        assert_eq!(image.get_pixel(18, 10), &style.cell); // linked wall is cut out
+        assert_eq!(image.get_pixel(10, 18), &style.wall); // unlinked wall remains
+    }
+
+    #[test]
+    fn check_draw_extreme_style() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+
+        for (cell_size, wall_thickness, margin) in [(4, 9, 0), (0, 0, 0), (1, 1, 2)].iter() {
+            let style = RenderStyle {
+                cell_size: *cell_size,
+                wall_thickness: *wall_thickness,
+                margin: *margin,
+                ..RenderStyle::default()
+            };
+
+            let image = grid.draw_image(&style);
+
+            assert_eq!(image.dimensions(), style.image_size(2, 2));
+        }
+    }
+
+    #[test]
+    fn check_draw_colour_map() {
+        let mut grid = Grid::grid(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        grid.apply_distances(Dijkstra::solve(&grid, (0, 0)).expect("Could not solve"));
+        let style = RenderStyle {
+            gradient: ColourMap::viridis(),
+            ..RenderStyle::default()
+        };
+
+        let image = grid.draw_image(&style);
+
+        assert_eq!(image.get_pixel(15, 15), &style.gradient.colour(0.0));
+        assert_eq!(image.get_pixel(25, 15), &style.gradient.colour(1.0));
+    }
+
+    #[test]
+    fn check_draw_inset() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        let style = RenderStyle {
+            inset: 0.25,
+            ..RenderStyle::inset()
+        };
+
+        let image = grid.draw_image(&style);
+
+        assert_eq!(image.get_pixel(22, 22), &style.background); // solid corner
+        assert_eq!(image.get_pixel(24, 30), &style.wall); // corridor edge
+        assert_eq!(image.get_pixel(30, 30), &style.cell); // cell centre
+        assert_eq!(image.get_pixel(40, 30), &style.cell); // corridor to the east
+        assert_eq!(image.get_pixel(30, 40), &style.background); // no corridor to the south
+    }
+
+    #[test]
+    fn check_draw_inset_tiny() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        let style = |cell_size| RenderStyle {
+            cell_size,
+            inset: 0.25,
+            ..RenderStyle::inset()
+        };
+
+        // a cell size below the clamp draws at the same scale as the canvas
+        assert_eq!(grid.draw_image(&style(1)), grid.draw_image(&style(2)));
+    }
+
+    #[test]
+    fn check_draw_inset_masked() {
+        let grid = Grid::grid(1, 2, |_, c| c == 0, &mut NoOp {});
+        let style = RenderStyle {
+            inset: 0.25,
+            masked: Rgb([0u8, 0u8, 0u8]),
+            ..RenderStyle::inset()
+        };
+
+        let image = grid.draw_image(&style);
+
+        assert_eq!(image.get_pixel(30, 30), &style.cell); // cell centre
+        assert_eq!(image.get_pixel(42, 22), &style.masked); // masked cell corner
+        assert_eq!(image.get_pixel(50, 30), &style.masked); // masked cell centre
+    }
+
+    #[test]
+    fn check_draw_svg_inset() {
+        let grid = Grid::square(1);
+
+        let svg = grid.draw_svg(&RenderStyle::inset(), false, None);
+
+        assert!(!svg.contains("<path"));
+        assert_eq!(svg.matches("<rect").count(), 3);
+    }
+
     #[test]
     fn check_draw() {
         let mut grid = Grid::grid(
@@ -625,7 +1244,7 @@ mod tests {
         grid.link_cell(&cell, Compass::East);
         grid.link_cell(&cell, Compass::West);
 
-        let image = grid.draw_image();
+        let image = grid.draw_image(&RenderStyle::default());
 
         assert_eq!(image.width(), 70);
         assert_eq!(image.height(), 70);
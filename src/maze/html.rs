@@ -0,0 +1,325 @@
+use crate::error::Result;
+use crate::maze::grid::Grid;
+use crate::maze::style::RenderStyle;
+use crate::maze::{Cell, Direction, Maze};
+use crate::solver::dijkstra::Dijkstra;
+use crate::solver::Distances;
+use crate::util::colourmap::ColourMap;
+use crate::util::svg::{escape, hex};
+
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+
+/// Script run in the page, reads the maze from the `maze-data` element
+const SCRIPT: &str = r##"
+const data = JSON.parse(document.getElementById("maze-data").textContent);
+const svg = document.querySelector("#maze svg");
+const ns = "http://www.w3.org/2000/svg";
+const cells = new Map(data.cells.map(c => [c.row + "," + c.column, c]));
+const centre = c => [data.margin + data.size * (c.column + 0.5), data.margin + data.size * (c.row + 0.5)];
+const status = document.getElementById("status");
+let route = [data.start];
+
+const heat = document.createElementNS(ns, "g");
+heat.style.display = "none";
+for (const c of data.cells) {
+  if (c.heat === null) continue;
+  const rect = document.createElementNS(ns, "rect");
+  rect.setAttribute("x", data.margin + data.size * c.column);
+  rect.setAttribute("y", data.margin + data.size * c.row);
+  rect.setAttribute("width", data.size);
+  rect.setAttribute("height", data.size);
+  rect.setAttribute("fill", c.heat);
+  heat.appendChild(rect);
+}
+svg.insertBefore(heat, svg.querySelector("path, polyline"));
+
+const solution = svg.querySelector("polyline");
+solution.style.display = "none";
+
+const trace = document.createElementNS(ns, "polyline");
+trace.setAttribute("stroke", data.trace);
+trace.setAttribute("stroke-width", data.size / 4);
+trace.setAttribute("stroke-linecap", "round");
+trace.setAttribute("stroke-linejoin", "round");
+trace.setAttribute("fill", "none");
+svg.appendChild(trace);
+
+function draw() {
+  trace.setAttribute("points", route.map(p => centre({row: p[0], column: p[1]}).join(",")).join(" "));
+  const [row, column] = route[route.length - 1];
+  status.textContent = row === data.goal[0] && column === data.goal[1]
+    ? "Solved in " + (route.length - 1) + " steps"
+    : "Steps: " + (route.length - 1);
+}
+
+function step(row, column) {
+  const [r, c] = route[route.length - 1];
+  const previous = route[route.length - 2];
+  if (previous && previous[0] === row && previous[1] === column) {
+    route.pop();
+  } else if (cells.get(r + "," + c).links.some(l => l[0] === row && l[1] === column)) {
+    route.push([row, column]);
+  }
+  draw();
+}
+
+const moves = {ArrowUp: [-1, 0], ArrowDown: [1, 0], ArrowLeft: [0, -1], ArrowRight: [0, 1]};
+document.addEventListener("keydown", e => {
+  const move = moves[e.key];
+  if (!move) return;
+  e.preventDefault();
+  const [r, c] = route[route.length - 1];
+  step(r + move[0], c + move[1]);
+});
+svg.addEventListener("click", e => {
+  const box = svg.getBoundingClientRect();
+  const scale = svg.viewBox.baseVal.width / box.width;
+  const column = Math.floor(((e.clientX - box.left) * scale - data.margin) / data.size);
+  const row = Math.floor(((e.clientY - box.top) * scale - data.margin) / data.size);
+  step(row, column);
+});
+document.getElementById("show-solution").addEventListener("change", e => {
+  solution.style.display = e.target.checked ? "" : "none";
+});
+document.getElementById("show-heat").addEventListener("change", e => {
+  heat.style.display = e.target.checked ? "" : "none";
+});
+document.getElementById("reset").addEventListener("click", () => {
+  route = [data.start];
+  draw();
+});
+draw();
+"##;
+
+/// Return the cells of a maze as a JSON array, each with the positions of its linked
+/// neighbours and its distance and heat map colour if reachable
+///
+/// # Arguments
+/// * `maze` - maze to describe, masked cells are left out
+/// * `distances` - distances used for the heat map
+/// * `gradient` - colours from the nearest to the furthest distance
+pub fn cells_json<T: Direction, M: Maze<T>>(
+    maze: &M,
+    distances: &Distances,
+    gradient: &ColourMap,
+) -> String {
+    let max = distances
+        .all_cells()
+        .values()
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(1);
+    let mut cells = Vec::new();
+
+    for cell in maze.cells() {
+        let neighbours = maze.neighbours(cell);
+        let mut links: Vec<(u32, u32)> = maze
+            .links(cell)
+            .iter()
+            .filter_map(|d| neighbours.get(d))
+            .map(|c| c.coords())
+            .collect();
+        links.sort_unstable();
+        let links: Vec<String> = links
+            .iter()
+            .map(|(r, c)| format!("[{},{}]", r, c))
+            .collect();
+        let (distance, heat) = match distances.distance(*cell) {
+            Ok(d) => (
+                d.to_string(),
+                format!("\"{}\"", hex(gradient.colour(d as f32 / max as f32))),
+            ),
+            Err(_) => ("null".to_string(), "null".to_string()),
+        };
+
+        cells.push(format!(
+            r#"{{"row":{},"column":{},"links":[{}],"distance":{},"heat":{}}}"#,
+            cell.row(),
+            cell.column(),
+            links.join(","),
+            distance,
+            heat
+        ));
+    }
+    format!("[{}]", cells.join(","))
+}
+
+/// Single page HTML export with the maze drawn as SVG and a script to play it
+///
+/// The player traces a route from the start with the arrow keys or by clicking the next cell,
+/// and can show the solution and a heat map of the distance from the start. Everything is
+/// embedded in the page so it can be opened straight from disk.
+/// ```
+///     Html::new(&grid, (0, 0), (9, 9))?.title("Maze").write("maze.html")?;
+/// ```
+pub struct Html<'a> {
+    grid: &'a Grid,
+    title: String,
+    style: RenderStyle,
+    start: Cell,
+    goal: Cell,
+    distances: Distances,
+    solution: Vec<Cell>,
+}
+
+#[allow(dead_code)]
+impl<'a> Html<'a> {
+    /// Build the page, solving the maze from the start to the goal
+    ///
+    /// # Arguments
+    /// * `grid` - maze to export
+    /// * `start` - row and column the player starts from
+    /// * `goal` - row and column the player is trying to reach
+    pub fn new(grid: &'a Grid, start: (u32, u32), goal: (u32, u32)) -> Result<Self> {
+        let start_cell = grid.valid_cell(start.0, start.1)?;
+        let goal_cell = grid.valid_cell(goal.0, goal.1)?;
+        let distances = Dijkstra::solve(grid, start)?;
+        let solution = distances.path_to(grid, goal_cell)?;
+
+        Ok(Html {
+            grid,
+            title: "Maze".to_string(),
+            style: RenderStyle::print(),
+            start: start_cell,
+            goal: goal_cell,
+            distances,
+            solution,
+        })
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Set the style used to draw the maze, the gradient colours the heat map
+    pub fn style(mut self, style: RenderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Return the maze as JSON for the page script
+    fn data(&self) -> String {
+        format!(
+            r#"{{"rows":{},"columns":{},"size":{},"margin":{},"start":[{},{}],"goal":[{},{}],"trace":"{}","cells":{}}}"#,
+            self.grid.rows(),
+            self.grid.columns(),
+            self.style.cell_size,
+            self.style.margin,
+            self.start.row(),
+            self.start.column(),
+            self.goal.row(),
+            self.goal.column(),
+            hex(self.style.solution),
+            cells_json(self.grid, &self.distances, &self.style.gradient)
+        )
+    }
+
+    /// Write the page to a file
+    pub fn write(&self, filename: &str) -> io::Result<()> {
+        fs::write(filename, self.to_string())
+    }
+}
+
+impl<'a> fmt::Display for Html<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = escape(&self.title);
+        let mut s = String::new();
+
+        writeln!(s, "<!DOCTYPE html>")?;
+        writeln!(s, "<html lang=\"en\">")?;
+        writeln!(s, "<head>")?;
+        writeln!(s, "<meta charset=\"utf-8\">")?;
+        writeln!(s, "<title>{}</title>", title)?;
+        writeln!(
+            s,
+            "<style>body {{ font-family: sans-serif; text-align: center; }} #maze svg {{ max-width: 100%; height: auto; cursor: pointer; }}</style>"
+        )?;
+        writeln!(s, "</head>")?;
+        writeln!(s, "<body>")?;
+        writeln!(s, "<h1>{}</h1>", title)?;
+        writeln!(
+            s,
+            "<p>Use the arrow keys or click the next cell to trace a route, step back to undo.</p>"
+        )?;
+        writeln!(s, "<div id=\"maze\">")?;
+        s.push_str(&self.grid.draw_svg(&self.style, false, Some(&self.solution)));
+        writeln!(s, "</div>")?;
+        writeln!(s, "<p id=\"status\" aria-live=\"polite\"></p>")?;
+        writeln!(
+            s,
+            "<p><label><input type=\"checkbox\" id=\"show-solution\"> Show solution</label> <label><input type=\"checkbox\" id=\"show-heat\"> Show distances</label> <button id=\"reset\">Reset</button></p>"
+        )?;
+        // JSON never contains `</` so it is safe inside the script element
+        writeln!(
+            s,
+            "<script type=\"application/json\" id=\"maze-data\">{}</script>",
+            self.data()
+        )?;
+        writeln!(s, "<script>{}</script>", SCRIPT)?;
+        writeln!(s, "</body>")?;
+        writeln!(s, "</html>")?;
+        f.write_str(&s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::maze::grid::Compass;
+    use crate::util::image::{BLUE, WHITE};
+
+    #[test]
+    fn check_cells_json() {
+        let mut grid = Grid::square(2);
+        let cell_00 = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell_00, Compass::East);
+        grid.link_cell(&cell_00, Compass::South);
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+
+        let json = cells_json(&grid, &distances, &ColourMap::linear(WHITE, BLUE));
+
+        assert!(json.starts_with(
+            r##"[{"row":0,"column":0,"links":[[0,1],[1,0]],"distance":0,"heat":"#ffffff"}"##
+        ));
+        assert!(json
+            .contains(r##"{"row":0,"column":1,"links":[[0,0]],"distance":1,"heat":"#0000ff"}"##));
+        assert!(json.ends_with(r#"{"row":1,"column":1,"links":[],"distance":null,"heat":null}]"#));
+    }
+
+    #[test]
+    fn check_page() {
+        let mut grid = Grid::square(2);
+        let cell_00 = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell_00, Compass::East);
+        let cell_01 = *grid.cell(0, 1).expect("Missing Cell 0,1");
+        grid.link_cell(&cell_01, Compass::South);
+
+        let page = Html::new(&grid, (0, 0), (1, 1))
+            .expect("Could not build page")
+            .title("<Test>")
+            .to_string();
+
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>&lt;Test&gt;</title>"));
+        assert!(page.contains("<svg "));
+        assert!(page.contains("<polyline"));
+        assert!(page.contains(r#""start":[0,0],"goal":[1,1]"#));
+        assert!(!page.contains(" src="));
+    }
+
+    #[test]
+    fn check_unreachable_goal() {
+        let grid = Grid::square(2);
+        let goal = *grid.cell(1, 1).expect("Missing Cell 1,1");
+
+        assert!(matches!(
+            Html::new(&grid, (0, 0), (1, 1)),
+            Err(Error::Unreachable(c)) if c == goal
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::error::{Error, Result};
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::style::RenderStyle;
+use crate::maze::Maze;
+use crate::router::internal::NoOp;
+
+use image::{Rgb, RgbImage};
+
+/// Where the cells are in an image of a maze and what colour the walls are
+///
+/// Wall lines are expected every `cell_size` pixels from the origin, with the same margin
+/// on the right and bottom as on the left and top.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ImageLayout {
+    /// width and height of each cell including one wall
+    pub cell_size: f32,
+    /// centre of the top left corner of the outer walls
+    pub origin: (f32, f32),
+    pub wall: Rgb<u8>,
+    /// colour filling masked cells, if any
+    pub masked: Option<Rgb<u8>>,
+    /// largest difference in any channel for a pixel to count as a colour
+    pub tolerance: u8,
+}
+
+#[allow(dead_code)]
+impl ImageLayout {
+    /// Layout of a scanned or external maze
+    ///
+    /// # Arguments
+    /// * `cell_size` - distance between wall lines in pixels
+    /// * `origin` - x and y of the centre of the top left outer wall corner
+    /// * `wall` - colour of the walls
+    pub fn new(cell_size: f32, origin: (f32, f32), wall: Rgb<u8>) -> Self {
+        ImageLayout {
+            cell_size,
+            origin,
+            wall,
+            masked: None,
+            tolerance: 64,
+        }
+    }
+
+    /// Layout of an image made by `draw_image` with the given style, drawn without distances
+    /// as the distance colours can be mistaken for masked cells
+    pub fn from_style(style: &RenderStyle) -> Self {
+        let origin = style.margin as f32 - style.wall_thickness as f32 / 2.0;
+        // stay closer to the masked colour than to an empty cell
+        let difference = style
+            .masked
+            .0
+            .iter()
+            .zip(style.cell.0.iter())
+            .map(|(a, b)| (*a as i16 - *b as i16).unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        ImageLayout {
+            masked: Some(style.masked),
+            tolerance: (difference / 2).min(64) as u8,
+            ..ImageLayout::new(style.cell_size as f32, (origin, origin), style.wall)
+        }
+    }
+
+    pub fn masked(mut self, masked: Rgb<u8>) -> Self {
+        self.masked = Some(masked);
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    fn matches(&self, pixel: &Rgb<u8>, colour: Rgb<u8>) -> bool {
+        pixel
+            .0
+            .iter()
+            .zip(colour.0.iter())
+            .all(|(a, b)| (*a as i16 - *b as i16).unsigned_abs() <= self.tolerance as u16)
+    }
+}
+
+/// Return true if any pixel on the line between the points is the colour
+fn sample(
+    image: &RgbImage,
+    layout: &ImageLayout,
+    from: (f32, f32),
+    to: (f32, f32),
+    colour: Rgb<u8>,
+) -> bool {
+    let steps = (to.0 - from.0)
+        .abs()
+        .max((to.1 - from.1).abs())
+        .ceil()
+        .max(1.0) as u32;
+    (0..=steps).any(|i| {
+        let t = i as f32 / steps as f32;
+        let x = (from.0 + (to.0 - from.0) * t).round();
+        let y = (from.1 + (to.1 - from.1) * t).round();
+        x >= 0.0
+            && y >= 0.0
+            && (x as u32) < image.width()
+            && (y as u32) < image.height()
+            && layout.matches(image.get_pixel(x as u32, y as u32), colour)
+    })
+}
+
+#[allow(dead_code)]
+impl Grid {
+    /// Rebuild a grid from an image of a maze, the inverse of `draw_image`
+    ///
+    /// Each wall is sampled across its middle, a quarter of a cell either side of where it
+    /// should be so slightly skewed scans still read, and the cells either side are linked if no
+    /// pixel matches the wall colour.
+    /// ```
+    ///     let image = image::open("maze.png")?.to_rgb8();
+    ///     let grid = Grid::from_image(&image, &ImageLayout::from_style(&RenderStyle::default()))?;
+    /// ```
+    pub fn from_image(image: &RgbImage, layout: &ImageLayout) -> Result<Grid> {
+        let size = layout.cell_size;
+        let (ox, oy) = layout.origin;
+        if size < 2.0 || ox < 0.0 || oy < 0.0 {
+            return Err(Error::InvalidFormat("cell size or origin out of range"));
+        }
+        let columns = ((image.width() as f32 - 2.0 * ox) / size).round() as u32;
+        let rows = ((image.height() as f32 - 2.0 * oy) / size).round() as u32;
+        if rows == 0 || columns == 0 {
+            return Err(Error::InvalidFormat("image is smaller than a cell"));
+        }
+        let centre = |r: u32, c: u32| (ox + size * (c as f32 + 0.5), oy + size * (r as f32 + 0.5));
+
+        let mut grid = Grid::grid(
+            rows,
+            columns,
+            |r, c| match layout.masked {
+                Some(masked) => {
+                    let (x, y) = centre(r, c);
+                    !sample(image, layout, (x, y), (x, y), masked)
+                }
+                None => true,
+            },
+            &mut NoOp {},
+        );
+
+        let reach = size / 4.0;
+        for row in 0..rows {
+            for column in 0..columns {
+                let cell = match grid.cell(row, column) {
+                    Some(c) => *c,
+                    None => continue,
+                };
+                let (x, y) = centre(row, column);
+                let east = ox + size * (column + 1) as f32;
+                let south = oy + size * (row + 1) as f32;
+                if column + 1 < columns
+                    && !sample(
+                        image,
+                        layout,
+                        (east - reach, y),
+                        (east + reach, y),
+                        layout.wall,
+                    )
+                {
+                    grid.link_cell(&cell, Compass::East);
+                }
+                if row + 1 < rows
+                    && !sample(
+                        image,
+                        layout,
+                        (x, south - reach),
+                        (x, south + reach),
+                        layout.wall,
+                    )
+                {
+                    grid.link_cell(&cell, Compass::South);
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::sidewinder::SideWinder;
+    use crate::util::image::{BLACK, WHITE};
+    use imageproc::{drawing, rect};
+    use rand::rngs::mock::StepRng;
+
+    fn maze() -> Grid {
+        let mut rng = StepRng::new(1, 1);
+        Grid::grid(
+            4,
+            6,
+            |r, c| r != 2 || c != 3,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        )
+    }
+
+    #[test]
+    fn check_round_trip() {
+        let grid = maze();
+
+        for style in [
+            RenderStyle::default(),
+            RenderStyle::print(),
+            RenderStyle::high_contrast(),
+        ]
+        .iter()
+        {
+            let image = grid.draw_image(style);
+
+            let read = Grid::from_image(&image, &ImageLayout::from_style(style))
+                .expect("Could not read image");
+
+            assert_eq!(read.to_string(), grid.to_string());
+        }
+    }
+
+    #[test]
+    fn check_scanned() {
+        // two cells side by side with a gap in the wall between them, drawn slightly off grid
+        let mut image = RgbImage::from_pixel(46, 26, WHITE);
+        let dark = Rgb([30u8, 20u8, 40u8]);
+        for (x, y, w, h) in [
+            (3, 3, 41, 2),
+            (3, 22, 41, 2),
+            (3, 3, 2, 21),
+            (42, 3, 2, 21),
+            (24, 3, 2, 6),
+            (24, 18, 2, 6),
+        ]
+        .iter()
+        {
+            drawing::draw_filled_rect_mut(&mut image, rect::Rect::at(*x, *y).of_size(*w, *h), dark);
+        }
+
+        let grid = Grid::from_image(&image, &ImageLayout::new(20.0, (4.0, 4.0), BLACK))
+            .expect("Could not read image");
+
+        assert_eq!(grid.size(), (1, 2));
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        assert!(grid.links(&cell).contains(&Compass::East));
+    }
+
+    #[test]
+    fn check_invalid() {
+        let image = RgbImage::from_pixel(5, 5, WHITE);
+
+        assert_eq!(
+            Grid::from_image(&image, &ImageLayout::new(10.0, (1.0, 1.0), BLACK)).unwrap_err(),
+            Error::InvalidFormat("image is smaller than a cell")
+        );
+        assert_eq!(
+            Grid::from_image(&image, &ImageLayout::new(1.0, (1.0, 1.0), BLACK)).unwrap_err(),
+            Error::InvalidFormat("cell size or origin out of range")
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::{Error, Result};
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::internal::MazeAccessor;
+use crate::maze::Maze;
+use crate::router::internal::NoOp;
+use crate::solver::Distances;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// Version written to every saved maze, bumped whenever the schema changes shape
+pub const VERSION: u32 = 1;
+
+/// Saved form of a grid
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "rows": 2,
+///   "columns": 2,
+///   "mask": ["..", ".#"],
+///   "links": [{"row": 0, "column": 0, "direction": "east"}],
+///   "weights": [null, 5, null, null],
+///   "distances": [0, 1, null, null]
+/// }
+/// ```
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct GridData {
+    pub version: u32,
+    pub rows: u32,
+    pub columns: u32,
+    /// one string per row, `.` for a cell and `#` for a masked cell
+    pub mask: Vec<String>,
+    /// every passage once, from a cell to its east or south neighbour
+    pub links: Vec<Link>,
+    /// weight of each cell in row order, null if masked or unweighted, the whole list is left
+    /// out if no cell has a weight
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<Option<u32>>>,
+    /// applied distance of each cell in row order, null if masked or unreached, the whole
+    /// list is left out if no distances are applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distances: Option<Vec<Option<u32>>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Link {
+    pub row: u32,
+    pub column: u32,
+    pub direction: Compass,
+}
+
+impl From<&Grid> for GridData {
+    fn from(grid: &Grid) -> Self {
+        let mut mask = Vec::new();
+        let mut links = Vec::new();
+        let mut weights = Vec::new();
+        let mut distances = Vec::new();
+
+        for row in 0..grid.rows() {
+            let mut line = String::new();
+            for column in 0..grid.columns() {
+                let cell = grid.cell(row, column);
+                line.push(if cell.is_some() { '.' } else { '#' });
+                weights.push(cell.and_then(|c| grid.weight(c)));
+                distances.push(cell.and_then(|c| grid._attributes(c).distance()));
+                if let Some(c) = cell {
+                    for direction in [Compass::East, Compass::South].iter() {
+                        if grid.links(c).contains(direction) {
+                            links.push(Link {
+                                row,
+                                column,
+                                direction: *direction,
+                            });
+                        }
+                    }
+                }
+            }
+            mask.push(line);
+        }
+
+        GridData {
+            version: VERSION,
+            rows: grid.rows(),
+            columns: grid.columns(),
+            mask,
+            links,
+            weights: if weights.iter().any(|w| w.is_some()) {
+                Some(weights)
+            } else {
+                None
+            },
+            distances: if distances.iter().any(|d| d.is_some()) {
+                Some(distances)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+impl From<Grid> for GridData {
+    fn from(grid: Grid) -> Self {
+        GridData::from(&grid)
+    }
+}
+
+impl TryFrom<GridData> for Grid {
+    type Error = Error;
+
+    fn try_from(data: GridData) -> Result<Self> {
+        if data.version != VERSION {
+            return Err(Error::InvalidFormat("unsupported version"));
+        }
+        let rows = data.rows as usize;
+        let columns = data.columns as usize;
+        if data.mask.len() != rows || data.mask.iter().any(|m| m.chars().count() != columns) {
+            return Err(Error::InvalidFormat("mask does not match the size"));
+        }
+        if data
+            .mask
+            .iter()
+            .any(|m| m.chars().any(|c| c != '.' && c != '#'))
+        {
+            return Err(Error::InvalidFormat("mask must only contain `.` and `#`"));
+        }
+
+        let mask: Vec<Vec<bool>> = data
+            .mask
+            .iter()
+            .map(|m| m.chars().map(|c| c == '.').collect())
+            .collect();
+        let mut grid = Grid::grid(
+            data.rows,
+            data.columns,
+            |r, c| mask[r as usize][c as usize],
+            &mut NoOp {},
+        );
+
+        for link in data.links {
+            let cell = grid.valid_cell(link.row, link.column)?;
+            if grid.link_cell(&cell, link.direction).is_none() {
+                return Err(Error::InvalidFormat("link leads out of the maze"));
+            }
+        }
+
+        if let Some(weights) = data.weights {
+            if weights.len() != rows * columns {
+                return Err(Error::InvalidFormat("weights do not match the size"));
+            }
+            for (offset, weight) in weights.iter().enumerate() {
+                if weight.is_some() {
+                    let cell =
+                        grid.valid_cell((offset / columns) as u32, (offset % columns) as u32)?;
+                    grid.set_weight(&cell, *weight);
+                }
+            }
+        }
+
+        if let Some(distances) = data.distances {
+            if distances.len() != rows * columns {
+                return Err(Error::InvalidFormat("distances do not match the size"));
+            }
+            let mut map = HashMap::new();
+            for (offset, distance) in distances.iter().enumerate() {
+                if let Some(d) = distance {
+                    let cell =
+                        grid.valid_cell((offset / columns) as u32, (offset % columns) as u32)?;
+                    map.insert(cell, *d);
+                }
+            }
+            grid.apply_distances(Distances::new(map));
+        }
+        Ok(grid)
+    }
+}
+
+#[allow(dead_code)]
+impl Grid {
+    /// Return the grid as pretty printed JSON in the versioned schema of `GridData`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&GridData::from(self)).expect("Grid data is always valid JSON")
+    }
+
+    /// Read a grid saved by `to_json`
+    pub fn from_json(json: &str) -> Result<Grid> {
+        let data: GridData =
+            serde_json::from_str(json).map_err(|_| Error::InvalidFormat("invalid JSON"))?;
+        Grid::try_from(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::{Cell, Direction};
+    use crate::router::sidewinder::SideWinder;
+    use crate::solver::dijkstra::Dijkstra;
+    use rand::rngs::mock::StepRng;
+
+    fn links(grid: &Grid) -> Vec<(Cell, Vec<Compass>)> {
+        grid.cells()
+            .into_iter()
+            .map(|c| {
+                let mut links: Vec<Compass> = grid.links(c).iter().copied().collect();
+                links.sort_by_key(|d| Compass::all().iter().position(|a| a == d));
+                (*c, links)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_round_trip() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            4,
+            |r, c| r != 1 || c != 1,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let loaded = Grid::from_json(&grid.to_json()).expect("Could not load grid");
+
+        assert_eq!(loaded.size(), (3, 4));
+        assert!(loaded.cell(1, 1).is_none());
+        assert_eq!(links(&loaded), links(&grid));
+        assert_eq!(loaded.to_string(), grid.to_string());
+    }
+
+    #[test]
+    fn check_round_trip_distances() {
+        let mut rng = StepRng::new(1, 1);
+        let mut grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+        grid.apply_distances(Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze"));
+
+        let loaded: Grid =
+            serde_json::from_str(&serde_json::to_string(&grid).expect("Could not save grid"))
+                .expect("Could not load grid");
+
+        for cell in grid.cells() {
+            assert_eq!(
+                loaded._attributes(cell).distance(),
+                grid._attributes(cell).distance()
+            );
+        }
+        assert_eq!(GridData::from(&loaded), GridData::from(&grid));
+    }
+
+    #[test]
+    fn check_round_trip_weights() {
+        let mut grid = Grid::grid(2, 3, Grid::ALLOW_ALL, &mut NoOp {});
+        let cell = *grid.cell(1, 2).expect("Missing Cell 1,2");
+        grid.set_weight(&cell, Some(5));
+
+        let data = GridData::from(&grid);
+        let loaded = Grid::try_from(data.clone()).expect("Could not load grid");
+
+        assert_eq!(
+            data.weights,
+            Some(vec![None, None, None, None, None, Some(5)])
+        );
+        assert_eq!(loaded.weight(&cell), Some(5));
+        assert_eq!(
+            loaded.weight(grid.cell(0, 0).expect("Missing Cell 0,0")),
+            None
+        );
+    }
+
+    #[test]
+    fn check_schema() {
+        let mut grid = Grid::grid(2, 2, |r, c| r != 1 || c != 1, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        grid.link_cell(&cell, Compass::South);
+
+        let json: serde_json::Value = serde_json::from_str(&grid.to_json()).expect("Invalid JSON");
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": 1,
+                "rows": 2,
+                "columns": 2,
+                "mask": ["..", ".#"],
+                "links": [
+                    {"row": 0, "column": 0, "direction": "east"},
+                    {"row": 0, "column": 0, "direction": "south"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn check_invalid() {
+        assert_eq!(
+            Grid::from_json("{").unwrap_err(),
+            Error::InvalidFormat("invalid JSON")
+        );
+        assert_eq!(
+            Grid::from_json(r#"{"version":2,"rows":1,"columns":1,"mask":["."],"links":[]}"#)
+                .unwrap_err(),
+            Error::InvalidFormat("unsupported version")
+        );
+        assert_eq!(
+            Grid::from_json(r#"{"version":1,"rows":1,"columns":2,"mask":["."],"links":[]}"#)
+                .unwrap_err(),
+            Error::InvalidFormat("mask does not match the size")
+        );
+        assert_eq!(
+            Grid::from_json(
+                r#"{"version":1,"rows":1,"columns":1,"mask":["."],"links":[{"row":0,"column":0,"direction":"east"}]}"#
+            )
+            .unwrap_err(),
+            Error::InvalidFormat("link leads out of the maze")
+        );
+        assert_eq!(
+            Grid::from_json(
+                r#"{"version":1,"rows":1,"columns":2,"mask":[".."],"links":[],"weights":[1]}"#
+            )
+            .unwrap_err(),
+            Error::InvalidFormat("weights do not match the size")
+        );
+        assert_eq!(
+            Grid::from_json(
+                r#"{"version":1,"rows":1,"columns":2,"mask":[".#"],"links":[],"weights":[1,2]}"#
+            )
+            .unwrap_err(),
+            Error::InvalidCell(0, 1)
+        );
+    }
+}
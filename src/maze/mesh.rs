@@ -0,0 +1,310 @@
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::Maze;
+
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::io;
+
+pub type Point3 = [f32; 3];
+/// Axis aligned box from its lowest to its highest corner
+pub type Cuboid = (Point3, Point3);
+
+/// Corners of each face of a cuboid, counter-clockwise seen from outside, indexed by the bits
+/// of the corner number: 1 for the high x, 2 for the high y and 4 for the high z
+const FACES: [([usize; 4], Point3); 6] = [
+    ([0, 2, 3, 1], [0.0, 0.0, -1.0]),
+    ([4, 5, 7, 6], [0.0, 0.0, 1.0]),
+    ([0, 1, 5, 4], [0.0, -1.0, 0.0]),
+    ([2, 6, 7, 3], [0.0, 1.0, 0.0]),
+    ([0, 4, 6, 2], [-1.0, 0.0, 0.0]),
+    ([1, 3, 7, 5], [1.0, 0.0, 0.0]),
+];
+
+/// 3D model of a grid for printing, the walls are extruded up from a base plate
+///
+/// Sizes are in millimetres, x runs along the columns and y up from the bottom row so the
+/// model matches the drawn maze seen from above. Masked cells have no base or walls.
+/// ```
+///     Mesh::new(&grid).wall_height(8.0).write_stl("maze.stl")?;
+/// ```
+pub struct Mesh<'a> {
+    grid: &'a Grid,
+    cell_size: f32,
+    wall_height: f32,
+    wall_thickness: f32,
+    base: f32,
+}
+
+#[allow(dead_code)]
+impl<'a> Mesh<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Mesh {
+            grid,
+            cell_size: 10.0,
+            wall_height: 5.0,
+            wall_thickness: 1.2,
+            base: 2.0,
+        }
+    }
+
+    /// Distance between the centres of the walls either side of a cell
+    pub fn cell_size(mut self, size: f32) -> Self {
+        self.cell_size = size;
+        self
+    }
+
+    /// Height of the walls above the base plate
+    pub fn wall_height(mut self, height: f32) -> Self {
+        self.wall_height = height;
+        self
+    }
+
+    pub fn wall_thickness(mut self, thickness: f32) -> Self {
+        self.wall_thickness = thickness;
+        self
+    }
+
+    /// Thickness of the base plate, 0 for free standing walls
+    pub fn base(mut self, thickness: f32) -> Self {
+        self.base = thickness.max(0.0);
+        self
+    }
+
+    /// Return the base plate and walls as boxes, which overlap at the corners
+    pub fn cuboids(&self) -> Vec<Cuboid> {
+        let size = self.cell_size;
+        let half = self.wall_thickness / 2.0;
+        let rows = self.grid.rows();
+        let top = self.base + self.wall_height;
+        // the wall lines of the grid, with the first row at the top
+        let x = |column: u32| half + size * column as f32;
+        let y = |row: u32| half + size * (rows - row) as f32;
+        let mut cuboids = Vec::new();
+
+        if self.base > 0.0 {
+            // one plate for each run of cells along a row
+            for row in 0..rows {
+                let mut column = 0;
+                while column < self.grid.columns() {
+                    if self.grid.cell(row, column).is_none() {
+                        column += 1;
+                        continue;
+                    }
+                    let start = column;
+                    while column < self.grid.columns() && self.grid.cell(row, column).is_some() {
+                        column += 1;
+                    }
+                    cuboids.push((
+                        [x(start) - half, y(row + 1) - half, 0.0],
+                        [x(column) + half, y(row) + half, self.base],
+                    ));
+                }
+            }
+        }
+
+        // each cell builds its north and west walls, and the east and south walls only on the
+        // edge of the maze or next to a masked cell
+        for cell in self.grid.cells() {
+            let (row, column) = cell.coords();
+            let links = self.grid.links(cell);
+            let neighbours = self.grid.neighbours(cell);
+            let horizontal = |r: u32| {
+                (
+                    [x(column) - half, y(r) - half, self.base],
+                    [x(column + 1) + half, y(r) + half, top],
+                )
+            };
+            let vertical = |c: u32| {
+                (
+                    [x(c) - half, y(row + 1) - half, self.base],
+                    [x(c) + half, y(row) + half, top],
+                )
+            };
+
+            if !links.contains(&Compass::North) {
+                cuboids.push(horizontal(row));
+            }
+            if !links.contains(&Compass::West) {
+                cuboids.push(vertical(column));
+            }
+            if !neighbours.contains_key(&Compass::East) {
+                cuboids.push(vertical(column + 1));
+            }
+            if !neighbours.contains_key(&Compass::South) {
+                cuboids.push(horizontal(row + 1));
+            }
+        }
+        cuboids
+    }
+
+    fn corners((low, high): &Cuboid) -> [Point3; 8] {
+        let mut corners = [[0.0; 3]; 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            for axis in 0..3 {
+                corner[axis] = if i & (1 << axis) != 0 {
+                    high[axis]
+                } else {
+                    low[axis]
+                };
+            }
+        }
+        corners
+    }
+
+    /// Return the model as binary STL
+    pub fn stl(&self) -> Vec<u8> {
+        let cuboids = self.cuboids();
+        let mut bytes = Vec::with_capacity(84 + cuboids.len() * 12 * 50);
+        let mut header = [0u8; 80];
+        header[..9].copy_from_slice(b"grid maze");
+
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&((cuboids.len() * 12) as u32).to_le_bytes());
+        for cuboid in &cuboids {
+            let corners = Mesh::corners(cuboid);
+            for (face, normal) in FACES.iter() {
+                for triangle in [[face[0], face[1], face[2]], [face[0], face[2], face[3]]].iter() {
+                    for value in normal {
+                        bytes.extend_from_slice(&value.to_le_bytes());
+                    }
+                    for corner in triangle {
+                        for value in &corners[*corner] {
+                            bytes.extend_from_slice(&value.to_le_bytes());
+                        }
+                    }
+                    bytes.extend_from_slice(&[0u8, 0u8]);
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Return the model as Wavefront OBJ, with a quad for each face
+    pub fn obj(&self) -> String {
+        let mut s = String::from("# grid maze\n");
+        for (index, cuboid) in self.cuboids().iter().enumerate() {
+            let _ = writeln!(s, "o wall{}", index);
+            for corner in Mesh::corners(cuboid).iter() {
+                let _ = writeln!(s, "v {} {} {}", corner[0], corner[1], corner[2]);
+            }
+            // vertex numbers count from one across the whole file
+            let first = index * 8 + 1;
+            for (face, _) in FACES.iter() {
+                let _ = writeln!(
+                    s,
+                    "f {} {} {} {}",
+                    first + face[0],
+                    first + face[1],
+                    first + face[2],
+                    first + face[3]
+                );
+            }
+        }
+        s
+    }
+
+    pub fn write_stl(&self, filename: &str) -> io::Result<()> {
+        fs::write(filename, self.stl())
+    }
+
+    pub fn write_obj(&self, filename: &str) -> io::Result<()> {
+        fs::write(filename, self.obj())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::internal::NoOp;
+
+    fn subtract(a: &Point3, b: &Point3) -> Point3 {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    #[test]
+    fn check_cuboids() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+
+        let mesh = Mesh::new(&grid).cell_size(10.0).wall_thickness(2.0);
+        let cuboids = mesh.cuboids();
+
+        // two base runs, then 2 + 2 + 3 + 4 walls
+        assert_eq!(cuboids.len(), 2 + 11);
+        assert_eq!(cuboids[0], ([0.0, 10.0, 0.0], [22.0, 22.0, 2.0]));
+        // north wall of the top left cell
+        assert_eq!(cuboids[2], ([0.0, 20.0, 2.0], [12.0, 22.0, 7.0]));
+        assert_eq!(Mesh::new(&grid).base(0.0).cuboids().len(), 11);
+    }
+
+    #[test]
+    fn check_masked() {
+        let grid = Grid::grid(1, 3, |_, c| c != 1, &mut NoOp {});
+
+        let cuboids = Mesh::new(&grid).cuboids();
+
+        // a base plate and four walls for each of the separate cells
+        assert_eq!(cuboids.len(), 10);
+        assert!(cuboids[0].1[0] < cuboids[1].0[0]);
+    }
+
+    #[test]
+    fn check_stl() {
+        let grid = Grid::square(1);
+        let mesh = Mesh::new(&grid);
+        let count = mesh.cuboids().len();
+
+        let stl = mesh.stl();
+
+        assert_eq!(stl.len(), 84 + count * 12 * 50);
+        assert_eq!(
+            u32::from_le_bytes([stl[80], stl[81], stl[82], stl[83]]) as usize,
+            count * 12
+        );
+    }
+
+    #[test]
+    fn check_winding() {
+        let cuboid = ([0.0, 0.0, 0.0], [1.0, 2.0, 3.0]);
+        let corners = Mesh::corners(&cuboid);
+
+        for (face, normal) in FACES.iter() {
+            let a = subtract(&corners[face[1]], &corners[face[0]]);
+            let b = subtract(&corners[face[2]], &corners[face[0]]);
+            let cross = [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ];
+            let dot: f32 = cross.iter().zip(normal.iter()).map(|(c, n)| c * n).sum();
+            assert!(dot > 0.0, "Face {:?} points inwards", face);
+        }
+    }
+
+    #[test]
+    fn check_obj() {
+        let grid = Grid::square(1);
+        let mesh = Mesh::new(&grid);
+        let count = mesh.cuboids().len();
+
+        let obj = mesh.obj();
+
+        assert_eq!(
+            obj.lines().filter(|l| l.starts_with("v ")).count(),
+            count * 8
+        );
+        assert_eq!(
+            obj.lines().filter(|l| l.starts_with("f ")).count(),
+            count * 6
+        );
+        assert!(obj.contains("o wall0\nv 0 0 0\n"));
+        assert!(obj.ends_with(&format!(
+            "f {0} {1} {2} {3}\n",
+            count * 8 - 6,
+            count * 8 - 4,
+            count * 8,
+            count * 8 - 2
+        )));
+    }
+}
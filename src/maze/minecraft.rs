@@ -0,0 +1,344 @@
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::Maze;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::{self, Write};
+
+/// Data version of Minecraft 1.20.1, newer versions upgrade older structures when loading
+pub const DATA_VERSION: i32 = 3465;
+
+/// What fills each column of blocks in the structure
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Column {
+    /// left out, so whatever is already in the world stays
+    Void,
+    Wall,
+    Open,
+}
+
+/// NBT tag, only the kinds needed for a structure
+enum Tag {
+    Int(i32),
+    String(String),
+    /// element type id and the elements
+    List(u8, Vec<Tag>),
+    Compound(Vec<(&'static str, Tag)>),
+}
+
+const INT: u8 = 3;
+const STRING: u8 = 8;
+const LIST: u8 = 9;
+const COMPOUND: u8 = 10;
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Int(_) => INT,
+            Tag::String(_) => STRING,
+            Tag::List(_, _) => LIST,
+            Tag::Compound(_) => COMPOUND,
+        }
+    }
+
+    fn ints(values: &[i32]) -> Tag {
+        Tag::List(INT, values.iter().map(|v| Tag::Int(*v)).collect())
+    }
+
+    fn write_string<W: Write>(out: &mut W, s: &str) -> io::Result<()> {
+        out.write_all(&(s.len() as u16).to_be_bytes())?;
+        out.write_all(s.as_bytes())
+    }
+
+    fn write_named<W: Write>(&self, out: &mut W, name: &str) -> io::Result<()> {
+        out.write_all(&[self.id()])?;
+        Tag::write_string(out, name)?;
+        self.write_payload(out)
+    }
+
+    fn write_payload<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        match self {
+            Tag::Int(value) => out.write_all(&value.to_be_bytes()),
+            Tag::String(s) => Tag::write_string(out, s),
+            Tag::List(id, elements) => {
+                out.write_all(&[*id])?;
+                out.write_all(&(elements.len() as i32).to_be_bytes())?;
+                for element in elements {
+                    element.write_payload(out)?;
+                }
+                Ok(())
+            }
+            Tag::Compound(tags) => {
+                for (name, tag) in tags {
+                    tag.write_named(out, name)?;
+                }
+                out.write_all(&[0])
+            }
+        }
+    }
+}
+
+/// Minecraft structure of a grid, in the NBT format saved by structure blocks
+///
+/// Each cell is a square corridor on a floor, with walls one block thick between the cells.
+/// Corridors are filled with air so placing the maze clears the ground, while masked cells
+/// are left out and keep whatever is in the world there. Load the file by copying it to
+/// `<world>/generated/<namespace>/structures/` and using a structure block or `/place`.
+/// ```
+///     Structure::new(&grid).wall_block("minecraft:hedge").write("maze.nbt")?;
+/// ```
+pub struct Structure<'a> {
+    grid: &'a Grid,
+    wall_block: String,
+    floor_block: String,
+    wall_height: u32,
+    corridor_width: u32,
+    data_version: i32,
+}
+
+#[allow(dead_code)]
+impl<'a> Structure<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Structure {
+            grid,
+            wall_block: "minecraft:stone_bricks".to_string(),
+            floor_block: "minecraft:smooth_stone".to_string(),
+            wall_height: 3,
+            corridor_width: 2,
+            data_version: DATA_VERSION,
+        }
+    }
+
+    /// Namespaced id of the block the walls are built from
+    pub fn wall_block(mut self, block: &str) -> Self {
+        self.wall_block = block.to_string();
+        self
+    }
+
+    /// Namespaced id of the block under the corridors and walls
+    pub fn floor_block(mut self, block: &str) -> Self {
+        self.floor_block = block.to_string();
+        self
+    }
+
+    /// Height of the walls above the floor in blocks
+    pub fn wall_height(mut self, height: u32) -> Self {
+        self.wall_height = height.max(1);
+        self
+    }
+
+    /// Width of each corridor in blocks
+    pub fn corridor_width(mut self, width: u32) -> Self {
+        self.corridor_width = width.max(1);
+        self
+    }
+
+    /// Data version of the game the structure is saved for
+    pub fn data_version(mut self, version: i32) -> Self {
+        self.data_version = version;
+        self
+    }
+
+    /// Return the width along x, height and length along z of the structure in blocks
+    pub fn size(&self) -> (u32, u32, u32) {
+        let step = self.corridor_width + 1;
+        (
+            self.grid.columns() * step + 1,
+            self.wall_height + 1,
+            self.grid.rows() * step + 1,
+        )
+    }
+
+    /// Return the plan of the maze seen from above, indexed by z then x
+    fn plan(&self) -> Vec<Vec<Column>> {
+        let (width, _, length) = self.size();
+        let step = self.corridor_width + 1;
+        let mut plan = vec![vec![Column::Void; width as usize]; length as usize];
+
+        for cell in self.grid.cells() {
+            let (x0, z0) = (cell.column() * step, cell.row() * step);
+            for z in z0..=z0 + step {
+                for x in x0..=x0 + step {
+                    let edge = x == x0 || z == z0 || x == x0 + step || z == z0 + step;
+                    let column = &mut plan[z as usize][x as usize];
+                    if !edge {
+                        *column = Column::Open;
+                    } else if *column == Column::Void {
+                        *column = Column::Wall;
+                    }
+                }
+            }
+        }
+
+        // open the walls between linked cells, leaving the corners standing
+        for cell in self.grid.cells() {
+            let (x0, z0) = (cell.column() * step, cell.row() * step);
+            let links = self.grid.links(cell);
+            for i in 1..step {
+                if links.contains(&Compass::East) {
+                    plan[(z0 + i) as usize][(x0 + step) as usize] = Column::Open;
+                }
+                if links.contains(&Compass::South) {
+                    plan[(z0 + step) as usize][(x0 + i) as usize] = Column::Open;
+                }
+            }
+        }
+        plan
+    }
+
+    fn root(&self) -> Tag {
+        const AIR: i32 = 0;
+        const FLOOR: i32 = 1;
+        const WALL: i32 = 2;
+        let (width, height, length) = self.size();
+        let mut blocks = Vec::new();
+        let mut block = |x: usize, y: u32, z: usize, state: i32| {
+            blocks.push(Tag::Compound(vec![
+                ("state", Tag::Int(state)),
+                ("pos", Tag::ints(&[x as i32, y as i32, z as i32])),
+            ]))
+        };
+
+        for (z, line) in self.plan().iter().enumerate() {
+            for (x, column) in line.iter().enumerate() {
+                if *column == Column::Void {
+                    continue;
+                }
+                block(x, 0, z, FLOOR);
+                for y in 1..height {
+                    block(x, y, z, if *column == Column::Wall { WALL } else { AIR });
+                }
+            }
+        }
+
+        let palette = ["minecraft:air", &self.floor_block, &self.wall_block]
+            .iter()
+            .map(|name| Tag::Compound(vec![("Name", Tag::String(name.to_string()))]))
+            .collect();
+        Tag::Compound(vec![
+            ("DataVersion", Tag::Int(self.data_version)),
+            (
+                "size",
+                Tag::ints(&[width as i32, height as i32, length as i32]),
+            ),
+            ("palette", Tag::List(COMPOUND, palette)),
+            ("blocks", Tag::List(COMPOUND, blocks)),
+            ("entities", Tag::List(COMPOUND, Vec::new())),
+        ])
+    }
+
+    /// Write the structure as gzipped NBT
+    pub fn write_nbt<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut encoder = GzEncoder::new(out, Compression::default());
+        self.root().write_named(&mut encoder, "")?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    /// Write the structure to an `.nbt` file
+    pub fn write(&self, filename: &str) -> io::Result<()> {
+        self.write_nbt(fs::File::create(filename)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::internal::NoOp;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn maze() -> Grid {
+        let mut grid = Grid::grid(2, 2, |r, c| r != 1 || c != 1, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        grid
+    }
+
+    fn render(plan: &[Vec<Column>]) -> String {
+        plan.iter()
+            .map(|line| {
+                line.iter()
+                    .map(|c| match c {
+                        Column::Void => ' ',
+                        Column::Wall => '#',
+                        Column::Open => '.',
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_plan() {
+        let grid = maze();
+        let structure = Structure::new(&grid);
+
+        assert_eq!(structure.size(), (7, 4, 7));
+        assert_eq!(
+            render(&structure.plan()),
+            "#######\n\
+             #.....#\n\
+             #.....#\n\
+             #######\n\
+             #..#   \n\
+             #..#   \n\
+             ####   \n"
+        );
+    }
+
+    #[test]
+    fn check_corridor_width() {
+        let grid = maze();
+        let structure = Structure::new(&grid).corridor_width(1).wall_height(1);
+
+        assert_eq!(structure.size(), (5, 2, 5));
+        assert_eq!(
+            render(&structure.plan()),
+            "#####\n\
+             #...#\n\
+             #####\n\
+             #.#  \n\
+             ###  \n"
+        );
+    }
+
+    #[test]
+    fn check_nbt() {
+        let grid = maze();
+        let mut bytes = Vec::new();
+        Structure::new(&grid)
+            .wall_block("minecraft:oak_planks")
+            .write_nbt(&mut bytes)
+            .expect("Could not write structure");
+
+        let mut nbt = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut nbt)
+            .expect("Could not decompress structure");
+
+        // an unnamed root compound starting with the data version
+        assert_eq!(&nbt[0..3], &[COMPOUND, 0, 0]);
+        assert_eq!(&nbt[3..17], b"\x03\x00\x0bDataVersion");
+        assert_eq!(&nbt[17..21], &DATA_VERSION.to_be_bytes());
+        // size is a list of three ints
+        assert_eq!(
+            &nbt[21..45],
+            b"\x09\x00\x04size\x03\x00\x00\x00\x03\x00\x00\x00\x07\x00\x00\x00\x04\x00\x00\x00\x07"
+        );
+        assert!(nbt
+            .windows(22)
+            .any(|w| w == b"\x00\x14minecraft:oak_planks"));
+        // 40 columns, each a floor block and three above it
+        let blocks = b"\x09\x00\x06blocks\x0a";
+        let start = nbt
+            .windows(blocks.len())
+            .position(|w| w == blocks)
+            .expect("Missing blocks")
+            + blocks.len();
+        assert_eq!(&nbt[start..start + 4], &(40 * 4i32).to_be_bytes());
+        assert_eq!(nbt.last(), Some(&0));
+    }
+}
@@ -0,0 +1,289 @@
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::Maze;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+
+pub type Point = (f32, f32);
+/// Straight line drawn or cut in one pass, from the first point to the second
+pub type Stroke = (Point, Point);
+
+/// Vector toolpaths along the centre of the walls, for laser cutters and pen plotters
+///
+/// Sizes are in millimetres with y up from the bottom row, as CAD and plotters expect. Walls
+/// in line are merged into one stroke, and strokes are ordered and turned so that each one
+/// starts near where the last one finished.
+/// ```
+///     let plot = Plot::new(&grid).cell_size(5.0);
+///     plot.write_dxf("maze.dxf")?;
+///     plot.write_gcode("maze.gcode")?;
+/// ```
+pub struct Plot<'a> {
+    grid: &'a Grid,
+    cell_size: f32,
+    feed_rate: f32,
+    pen_up: f32,
+}
+
+#[allow(dead_code)]
+impl<'a> Plot<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Plot {
+            grid,
+            cell_size: 10.0,
+            feed_rate: 1500.0,
+            pen_up: 5.0,
+        }
+    }
+
+    /// Distance between wall lines
+    pub fn cell_size(mut self, size: f32) -> Self {
+        self.cell_size = size;
+        self
+    }
+
+    /// Drawing speed in millimetres a minute
+    pub fn feed_rate(mut self, rate: f32) -> Self {
+        self.feed_rate = rate;
+        self
+    }
+
+    /// Height of the pen above the paper while moving between strokes
+    pub fn pen_up(mut self, height: f32) -> Self {
+        self.pen_up = height;
+        self
+    }
+
+    /// Return the walls merged into the fewest straight lines, in grid units with y down
+    fn lines(&self) -> Vec<((u32, u32), (u32, u32))> {
+        // unit walls keyed by the line they lie on, as the column or row they start at
+        let mut horizontal: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        let mut vertical: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        for cell in self.grid.cells() {
+            let (row, column) = cell.coords();
+            let links = self.grid.links(cell);
+            let neighbours = self.grid.neighbours(cell);
+            if !links.contains(&Compass::North) {
+                horizontal.entry(row).or_default().push(column);
+            }
+            if !links.contains(&Compass::West) {
+                vertical.entry(column).or_default().push(row);
+            }
+            if !neighbours.contains_key(&Compass::East) {
+                vertical.entry(column + 1).or_default().push(row);
+            }
+            if !neighbours.contains_key(&Compass::South) {
+                horizontal.entry(row + 1).or_default().push(column);
+            }
+        }
+
+        let mut lines = Vec::new();
+        for (walls, is_horizontal) in [(horizontal, true), (vertical, false)].iter_mut() {
+            for (line, starts) in walls.iter_mut() {
+                starts.sort_unstable();
+                starts.dedup();
+                let mut run: Option<(u32, u32)> = None;
+                for start in starts.iter() {
+                    run = match run {
+                        Some((from, to)) if to == *start => Some((from, start + 1)),
+                        Some((from, to)) => {
+                            lines.push(Plot::line(*is_horizontal, *line, from, to));
+                            Some((*start, start + 1))
+                        }
+                        None => Some((*start, start + 1)),
+                    };
+                }
+                if let Some((from, to)) = run {
+                    lines.push(Plot::line(*is_horizontal, *line, from, to));
+                }
+            }
+        }
+        lines
+    }
+
+    fn line(horizontal: bool, line: u32, from: u32, to: u32) -> ((u32, u32), (u32, u32)) {
+        if horizontal {
+            ((from, line), (to, line))
+        } else {
+            ((line, from), (line, to))
+        }
+    }
+
+    /// Return the strokes in drawing order, starting nearest the origin
+    ///
+    /// Each stroke is the nearest unvisited end to the end of the previous one, which keeps
+    /// pen-up travel short without the cost of an exact route.
+    pub fn strokes(&self) -> Vec<Stroke> {
+        let height = self.grid.rows() as f32 * self.cell_size;
+        let point = |(x, y): (u32, u32)| {
+            (
+                x as f32 * self.cell_size,
+                height - y as f32 * self.cell_size,
+            )
+        };
+        let mut remaining: Vec<Stroke> = self
+            .lines()
+            .into_iter()
+            .map(|(from, to)| (point(from), point(to)))
+            .collect();
+
+        let mut strokes = Vec::with_capacity(remaining.len());
+        let mut position = (0.0, 0.0);
+        while !remaining.is_empty() {
+            let mut best = (0, false, f32::MAX);
+            for (index, (from, to)) in remaining.iter().enumerate() {
+                for (end, reversed) in [(from, false), (to, true)].iter() {
+                    let d = distance(position, **end);
+                    if d < best.2 {
+                        best = (index, *reversed, d);
+                    }
+                }
+            }
+            let (from, to) = remaining.swap_remove(best.0);
+            let stroke = if best.1 { (to, from) } else { (from, to) };
+            position = stroke.1;
+            strokes.push(stroke);
+        }
+        strokes
+    }
+
+    /// Return the total distance moved with the pen up between strokes, from the origin
+    pub fn travel(strokes: &[Stroke]) -> f32 {
+        let mut position = (0.0, 0.0);
+        let mut travel = 0.0;
+        for (from, to) in strokes {
+            travel += distance(position, *from);
+            position = *to;
+        }
+        travel
+    }
+
+    /// Return the strokes as an ASCII DXF drawing of lines
+    pub fn dxf(&self) -> String {
+        let mut s = String::new();
+        s.push_str("0\nSECTION\n2\nHEADER\n9\n$INSUNITS\n70\n4\n0\nENDSEC\n");
+        s.push_str("0\nSECTION\n2\nENTITIES\n");
+        for ((x1, y1), (x2, y2)) in self.strokes() {
+            let _ = write!(
+                s,
+                "0\nLINE\n8\nWALLS\n10\n{}\n20\n{}\n30\n0\n11\n{}\n21\n{}\n31\n0\n",
+                x1, y1, x2, y2
+            );
+        }
+        s.push_str("0\nENDSEC\n0\nEOF\n");
+        s
+    }
+
+    /// Return the strokes as G-code for a pen plotter, which lowers the pen to z 0 to draw
+    pub fn gcode(&self) -> String {
+        let mut s = String::new();
+        let _ = writeln!(s, "G21 ; millimetres");
+        let _ = writeln!(s, "G90 ; absolute positions");
+        let _ = writeln!(s, "G0 Z{:.2}", self.pen_up);
+        for ((x1, y1), (x2, y2)) in self.strokes() {
+            let _ = writeln!(s, "G0 X{:.2} Y{:.2}", x1, y1);
+            let _ = writeln!(s, "G1 Z0 F{:.0}", self.feed_rate);
+            let _ = writeln!(s, "G1 X{:.2} Y{:.2}", x2, y2);
+            let _ = writeln!(s, "G0 Z{:.2}", self.pen_up);
+        }
+        let _ = writeln!(s, "G0 X0 Y0");
+        s
+    }
+
+    pub fn write_dxf(&self, filename: &str) -> io::Result<()> {
+        fs::write(filename, self.dxf())
+    }
+
+    pub fn write_gcode(&self, filename: &str) -> io::Result<()> {
+        fs::write(filename, self.gcode())
+    }
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::internal::NoOp;
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_merged() {
+        let grid = Grid::square(3);
+
+        let strokes = Plot::new(&grid).strokes();
+
+        // four lines across and four down, each the full width of the grid
+        assert_eq!(strokes.len(), 8);
+        assert!(strokes.iter().all(|(a, b)| distance(*a, *b) == 30.0));
+    }
+
+    #[test]
+    fn check_masked() {
+        let grid = Grid::grid(1, 3, |_, c| c != 1, &mut NoOp {});
+
+        let strokes = Plot::new(&grid).strokes();
+
+        // the top and bottom are broken by the masked cell
+        assert_eq!(strokes.len(), 8);
+        assert!(!strokes
+            .iter()
+            .any(|((x1, _), (x2, _))| x1.min(*x2) < 15.0 && x1.max(*x2) > 15.0));
+    }
+
+    #[test]
+    fn check_order() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            6,
+            6,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+        let plot = Plot::new(&grid);
+        let unordered: Vec<Stroke> = plot
+            .lines()
+            .into_iter()
+            .map(|((x1, y1), (x2, y2))| {
+                (
+                    (x1 as f32 * 10.0, 60.0 - y1 as f32 * 10.0),
+                    (x2 as f32 * 10.0, 60.0 - y2 as f32 * 10.0),
+                )
+            })
+            .collect();
+
+        let strokes = plot.strokes();
+
+        assert_eq!(strokes.len(), unordered.len());
+        assert_eq!(strokes[0].0, (0.0, 0.0));
+        assert!(Plot::travel(&strokes) < Plot::travel(&unordered));
+    }
+
+    #[test]
+    fn check_dxf() {
+        let grid = Grid::square(1);
+
+        let dxf = Plot::new(&grid).cell_size(5.0).dxf();
+
+        assert_eq!(dxf.matches("0\nLINE\n").count(), 4);
+        assert!(dxf.contains("0\nSECTION\n2\nENTITIES\n0\nLINE\n8\nWALLS\n10\n0\n20\n0\n"));
+        assert!(dxf.ends_with("0\nENDSEC\n0\nEOF\n"));
+    }
+
+    #[test]
+    fn check_gcode() {
+        let grid = Grid::square(1);
+
+        let gcode = Plot::new(&grid).feed_rate(1000.0).gcode();
+
+        assert!(gcode.starts_with("G21 ; millimetres\nG90 ; absolute positions\nG0 Z5.00\n"));
+        assert!(gcode.contains("G0 X0.00 Y0.00\nG1 Z0 F1000\nG1 X"));
+        assert_eq!(gcode.matches("G1 Z0").count(), 4);
+        assert!(gcode.ends_with("G0 Z5.00\nG0 X0 Y0\n"));
+    }
+}
@@ -0,0 +1,507 @@
+use crate::error::Result;
+use crate::maze::grid::Grid;
+use crate::maze::style::RenderStyle;
+use crate::maze::{Cell, Maze};
+use crate::solver::dijkstra::Dijkstra;
+use crate::util::font;
+use crate::util::image::WHITE;
+use crate::util::svg::Document;
+
+use image::{imageops, ImageResult, RgbImage};
+use imageproc::{drawing, rect};
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// Page sizes in inches
+pub const LETTER: (f32, f32) = (8.5, 11.0);
+#[allow(dead_code)]
+pub const A4: (f32, f32) = (8.27, 11.69);
+
+/// Rough difficulty of a maze, printed on the sheet
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Rate a maze by the number of junctions along the solution, each one is a choice the
+    /// solver could get wrong
+    pub fn rate(grid: &Grid, solution: &[Cell]) -> Difficulty {
+        let junctions = solution.iter().filter(|c| grid.links(c).len() > 2).count();
+
+        match junctions {
+            0..=4 => Difficulty::Easy,
+            5..=14 => Difficulty::Medium,
+            _ => Difficulty::Hard,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Print ready puzzle page with a title, start and finish markers, seed and difficulty, and a
+/// matching answer page with the solution drawn in
+///
+/// Pages are laid out in pixels at the given DPI, the SVG pages keep the same layout and set
+/// their printed size in inches.
+/// ```
+///     let sheet = Sheet::new(&grid, "Maze 1", (0, 0), (9, 9))?.seed(42);
+///     sheet.write_png("puzzle.png", "answer.png")?;
+/// ```
+pub struct Sheet<'a> {
+    grid: &'a Grid,
+    title: String,
+    start: Cell,
+    goal: Cell,
+    solution: Vec<Cell>,
+    seed: Option<u64>,
+    spec: Option<String>,
+    difficulty: Difficulty,
+    dpi: u32,
+    page: (f32, f32),
+    style: RenderStyle,
+}
+
+/// Positions on the page in pixels, with the style scaled so the maze fills the space left
+struct Layout {
+    width: u32,
+    height: u32,
+    title_y: u32,
+    title_size: u32,
+    info_y: u32,
+    info_size: u32,
+    maze_x: u32,
+    maze_y: u32,
+    style: RenderStyle,
+}
+
+#[allow(dead_code)]
+impl<'a> Sheet<'a> {
+    /// Build a sheet for the grid, solving it from the start to the goal
+    ///
+    /// # Arguments
+    /// * `grid` - maze to print, any applied distances are ignored
+    /// * `title` - heading printed at the top of both pages
+    /// * `start` - row and column of the start cell
+    /// * `goal` - row and column of the finish cell
+    pub fn new(grid: &'a Grid, title: &str, start: (u32, u32), goal: (u32, u32)) -> Result<Self> {
+        let start_cell = grid.valid_cell(start.0, start.1)?;
+        let goal_cell = grid.valid_cell(goal.0, goal.1)?;
+        let solution = Dijkstra::solve(grid, start)?.path_to(grid, goal_cell)?;
+
+        Ok(Sheet {
+            grid,
+            title: title.to_string(),
+            start: start_cell,
+            goal: goal_cell,
+            difficulty: Difficulty::rate(grid, &solution),
+            solution,
+            seed: None,
+            spec: None,
+            dpi: 300,
+            page: LETTER,
+            style: RenderStyle::print(),
+        })
+    }
+
+    /// Print the seed used to generate the maze so it can be made again
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Print the spec the maze was carved from, such as `grid:20x20:sidewinder:seed=42`, so
+    /// the exact maze can be made again
+    pub fn spec(mut self, spec: &str) -> Self {
+        self.spec = Some(spec.to_string());
+        self
+    }
+
+    /// Replace the rated difficulty
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    pub fn dpi(mut self, dpi: u32) -> Self {
+        self.dpi = dpi.max(10);
+        self
+    }
+
+    /// Set the page width and height in inches
+    pub fn page(mut self, page: (f32, f32)) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// Set the colours and wall thickness, the cell size and margin are fitted to the page
+    pub fn style(mut self, style: RenderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn solution(&self) -> &[Cell] {
+        &self.solution
+    }
+
+    pub fn rating(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Line of details printed under the title
+    fn info(&self) -> String {
+        let mut info = Vec::new();
+        if let Some(spec) = &self.spec {
+            info.push(format!("Spec: {}", spec));
+        }
+        if let Some(seed) = self.seed {
+            info.push(format!("Seed: {}", seed));
+        }
+        info.push(format!("Difficulty: {}", self.difficulty));
+        info.push(format!("{} x {}", self.grid.rows(), self.grid.columns()));
+        info.join("   ")
+    }
+
+    fn heading(&self, answer: bool) -> String {
+        if answer {
+            format!("{} - Solution", self.title)
+        } else {
+            self.title.clone()
+        }
+    }
+
+    fn layout(&self) -> Layout {
+        let width = (self.page.0 * self.dpi as f32).round() as u32;
+        let height = (self.page.1 * self.dpi as f32).round() as u32;
+        let margin = self.dpi / 2;
+        let title_size = self.dpi * 3 / 8;
+        // shrink the details to fit across the page, a spec can be long
+        let (info_width, _) = font::text_size(&self.info(), 1);
+        let info_size = (self.dpi / 6)
+            .min(width.saturating_sub(2 * margin) / info_width.max(1) * font::GLYPH_HEIGHT)
+            .max(font::GLYPH_HEIGHT);
+        let title_y = margin;
+        let info_y = title_y + title_size + self.dpi / 8;
+        let top = info_y + info_size + self.dpi / 4;
+        let rows = self.grid.rows();
+        let columns = self.grid.columns();
+
+        let cell_size = ((width.saturating_sub(2 * margin)) / columns)
+            .min(height.saturating_sub(top + margin) / rows)
+            .max(2);
+        let wall = (cell_size / 10)
+            .max(self.style.wall_thickness)
+            .min(cell_size / 2)
+            .max(1);
+        let style = RenderStyle {
+            cell_size,
+            wall_thickness: wall,
+            margin: wall,
+            ..self.style.clone()
+        };
+        let (maze_width, _) = style.image_size(rows, columns);
+
+        Layout {
+            width,
+            height,
+            title_y,
+            title_size,
+            info_y,
+            info_size,
+            maze_x: width.saturating_sub(maze_width) / 2,
+            maze_y: top,
+            style,
+        }
+    }
+
+    /// Return the centre of a cell on the page
+    fn centre(layout: &Layout, cell: &Cell) -> (u32, u32) {
+        let size = layout.style.cell_size;
+        (
+            layout.maze_x + layout.style.margin + size * cell.column() + size / 2,
+            layout.maze_y + layout.style.margin + size * cell.row() + size / 2,
+        )
+    }
+
+    /// Draw text centred on x with its top edge at y, scaled to fit the given height
+    fn draw_centred(&self, image: &mut RgbImage, x: u32, y: u32, height: u32, text: &str) {
+        let scale = (height / font::GLYPH_HEIGHT).max(1);
+        let (w, _) = font::text_size(text, scale);
+        font::draw_text(
+            image,
+            x as i32 - w as i32 / 2,
+            y as i32,
+            scale,
+            self.style.wall,
+            text,
+        );
+    }
+
+    fn page_image(&self, answer: bool) -> RgbImage {
+        let layout = self.layout();
+        let size = layout.style.cell_size;
+        let mut image = RgbImage::from_pixel(layout.width, layout.height, WHITE);
+
+        self.draw_centred(
+            &mut image,
+            layout.width / 2,
+            layout.title_y,
+            layout.title_size,
+            &self.heading(answer),
+        );
+        self.draw_centred(
+            &mut image,
+            layout.width / 2,
+            layout.info_y,
+            layout.info_size,
+            &self.info(),
+        );
+
+        // the puzzle is printed plain, whatever distances are applied to the grid
+        let mut grid = self.grid.clone();
+        grid.clear_distances();
+        imageops::overlay(
+            &mut image,
+            &grid.draw_image(&layout.style),
+            layout.maze_x,
+            layout.maze_y,
+        );
+
+        if answer {
+            let thickness = (size / 4).max(1);
+            for pair in self.solution.windows(2) {
+                let (x1, y1) = Sheet::centre(&layout, &pair[0]);
+                let (x2, y2) = Sheet::centre(&layout, &pair[1]);
+                drawing::draw_filled_rect_mut(
+                    &mut image,
+                    rect::Rect::at(
+                        (x1.min(x2) - thickness / 2) as i32,
+                        (y1.min(y2) - thickness / 2) as i32,
+                    )
+                    .of_size(
+                        x1.max(x2) - x1.min(x2) + thickness,
+                        y1.max(y2) - y1.min(y2) + thickness,
+                    ),
+                    layout.style.solution,
+                );
+            }
+        }
+
+        // markers sit on a patch of the cell colour so they stay readable over the solution
+        let marker = size * 3 / 5;
+        for (cell, label) in [(self.start, "S"), (self.goal, "F")].iter() {
+            let (x, y) = Sheet::centre(&layout, cell);
+            drawing::draw_filled_rect_mut(
+                &mut image,
+                rect::Rect::at((x - marker / 2) as i32, (y - marker / 2) as i32)
+                    .of_size(marker.max(1), marker.max(1)),
+                layout.style.cell,
+            );
+            self.draw_centred(&mut image, x, y - marker / 2, marker, label);
+        }
+        image
+    }
+
+    fn page_svg(&self, answer: bool) -> String {
+        let layout = self.layout();
+        let size = layout.style.cell_size as f32;
+        let mut document = Document::new(layout.width, layout.height, layout.style.antialias)
+            .inches(self.page.0, self.page.1);
+        let centre = layout.width as f32 / 2.0;
+
+        document.rect(0.0, 0.0, layout.width as f32, layout.height as f32, WHITE);
+        document.text(
+            centre,
+            (layout.title_y + layout.title_size / 2) as f32,
+            layout.title_size as f32,
+            self.style.wall,
+            &self.heading(answer),
+        );
+        document.text(
+            centre,
+            (layout.info_y + layout.info_size / 2) as f32,
+            layout.info_size as f32,
+            self.style.wall,
+            &self.info(),
+        );
+        let solution = if answer {
+            Some(self.solution.as_slice())
+        } else {
+            None
+        };
+        document.embed(
+            layout.maze_x as f32,
+            layout.maze_y as f32,
+            &self.grid.draw_svg(&layout.style, false, solution),
+        );
+        let marker = size * 0.6;
+        for (cell, label) in [(self.start, "S"), (self.goal, "F")].iter() {
+            let (x, y) = Sheet::centre(&layout, cell);
+            let (x, y) = (x as f32, y as f32);
+            document.rect(
+                x - marker / 2.0,
+                y - marker / 2.0,
+                marker,
+                marker,
+                layout.style.cell,
+            );
+            document.text(x, y, marker, self.style.wall, label);
+        }
+        document.finish()
+    }
+
+    pub fn puzzle_image(&self) -> RgbImage {
+        self.page_image(false)
+    }
+
+    pub fn answer_image(&self) -> RgbImage {
+        self.page_image(true)
+    }
+
+    pub fn puzzle_svg(&self) -> String {
+        self.page_svg(false)
+    }
+
+    pub fn answer_svg(&self) -> String {
+        self.page_svg(true)
+    }
+
+    /// Write the puzzle and answer pages as PNG images
+    pub fn write_png(&self, puzzle: &str, answer: &str) -> ImageResult<()> {
+        self.puzzle_image().save(puzzle)?;
+        self.answer_image().save(answer)
+    }
+
+    /// Write the puzzle and answer pages as SVG documents
+    pub fn write_svg(&self, puzzle: &str, answer: &str) -> io::Result<()> {
+        fs::write(puzzle, self.puzzle_svg())?;
+        fs::write(answer, self.answer_svg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::maze::grid::Compass;
+    use crate::router::sidewinder::SideWinder;
+    use crate::util::image::BLACK;
+    use rand::rngs::mock::StepRng;
+
+    fn maze() -> Grid {
+        let mut rng = StepRng::new(1, 1);
+        Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        )
+    }
+
+    #[test]
+    fn check_solution() {
+        let grid = maze();
+        let sheet = Sheet::new(&grid, "Test", (0, 0), (2, 2)).expect("Could not build sheet");
+
+        assert_eq!(sheet.solution().first().map(|c| c.coords()), Some((0, 0)));
+        assert_eq!(sheet.solution().last().map(|c| c.coords()), Some((2, 2)));
+        assert_eq!(sheet.rating(), Difficulty::Easy);
+    }
+
+    #[test]
+    fn check_invalid_goal() {
+        let grid = maze();
+
+        assert_eq!(
+            Sheet::new(&grid, "Test", (0, 0), (3, 3)).err(),
+            Some(Error::InvalidCell(3, 3))
+        );
+    }
+
+    #[test]
+    fn check_info() {
+        let grid = maze();
+        let sheet = Sheet::new(&grid, "Test", (0, 0), (2, 2))
+            .expect("Could not build sheet")
+            .seed(42)
+            .difficulty(Difficulty::Hard);
+
+        assert_eq!(sheet.info(), "Seed: 42   Difficulty: Hard   3 x 3");
+    }
+
+    #[test]
+    fn check_spec() {
+        let grid = maze();
+        let spec = "grid:3x3:sidewinder:seed=17249659875909523276";
+        let sheet = Sheet::new(&grid, "Test", (0, 0), (2, 2))
+            .expect("Could not build sheet")
+            .spec(spec);
+        let layout = sheet.layout();
+
+        assert_eq!(
+            sheet.info(),
+            format!("Spec: {}   Difficulty: Easy   3 x 3", spec)
+        );
+        // the details are drawn smaller so they stay inside the page margins
+        let scale = layout.info_size / font::GLYPH_HEIGHT;
+        let (width, _) = font::text_size(&sheet.info(), scale);
+        assert!(scale < 300 / 6 / font::GLYPH_HEIGHT);
+        assert!(width <= layout.width - 300);
+    }
+
+    #[test]
+    fn check_page_image() {
+        let grid = maze();
+        let sheet = Sheet::new(&grid, "Test", (0, 0), (2, 2))
+            .expect("Could not build sheet")
+            .dpi(100)
+            .page((4.0, 5.0));
+        let layout = sheet.layout();
+
+        let puzzle = sheet.puzzle_image();
+        let answer = sheet.answer_image();
+
+        assert_eq!(puzzle.dimensions(), (400, 500));
+        assert_eq!(layout.style.cell_size, 100);
+        // the solution runs along the top and down the right, missing the middle cell
+        let on_path = *grid.cell(1, 2).expect("Missing cell 1,2");
+        let off_path = *grid.cell(1, 1).expect("Missing cell 1,1");
+        assert!(sheet.solution().contains(&on_path));
+        assert!(!sheet.solution().contains(&off_path));
+
+        let (x, y) = Sheet::centre(&layout, &on_path);
+        assert_eq!(puzzle.get_pixel(x, y), &layout.style.cell);
+        assert_eq!(answer.get_pixel(x, y), &BLACK);
+        let (x, y) = Sheet::centre(&layout, &off_path);
+        assert_eq!(answer.get_pixel(x, y), &layout.style.cell);
+    }
+
+    #[test]
+    fn check_page_svg() {
+        let grid = maze();
+        let sheet = Sheet::new(&grid, "Fish & Chips", (0, 0), (2, 2))
+            .expect("Could not build sheet")
+            .page(A4);
+
+        let puzzle = sheet.puzzle_svg();
+        let answer = sheet.answer_svg();
+
+        assert!(puzzle.contains(r#"width="8.27in""#));
+        assert!(puzzle.contains(">Fish &amp; Chips</text>"));
+        assert!(!puzzle.contains("<polyline"));
+        assert!(answer.contains(">Fish &amp; Chips - Solution</text>"));
+        assert!(answer.contains("<polyline"));
+    }
+}
@@ -0,0 +1,469 @@
+use crate::error::{Error, Result};
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::{Cell, Direction, Maze};
+use crate::router::binarytree::BinaryTree;
+use crate::router::sidewinder::SideWinder;
+use crate::router::Router;
+use crate::solver::breadth_first;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// Shape of the maze, only square grids so far
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GridType {
+    Grid,
+}
+
+/// Cells left out of the maze
+///
+/// The routers only carve towards their own directions, so a mask can cut regions off. Any
+/// region left unreachable is joined to the rest through a wall picked with the same seed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Mask {
+    None,
+    /// the four corner cells
+    Corners,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RouterKind {
+    BinaryTree,
+    SideWinder,
+}
+
+/// Everything needed to carve a maze again, written as a short string
+///
+/// The format is `<type>:<rows>x<columns>:<router>` followed by any options as `:key=value`:
+/// * `mask` - `none` or `corners`
+/// * `directions` - the two directions the router carves towards, e.g. `north,east`
+/// * `seed` - seed for the random numbers, written last
+///
+/// Options left at their default are not written, except the seed which always is. The random numbers come from ChaCha8,
+/// which gives the same sequence for a seed on every platform, so a spec such as
+/// `grid:20x20:sidewinder:seed=42` always carves the same maze.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MazeSpec {
+    pub grid_type: GridType,
+    pub rows: u32,
+    pub columns: u32,
+    pub mask: Mask,
+    pub router: RouterKind,
+    pub directions: (Compass, Compass),
+    pub seed: u64,
+}
+
+/// Return true for every cell except the corners of the grid
+pub fn mask_corners(rows: u32, columns: u32) -> impl Fn(u32, u32) -> bool {
+    move |r, c| !((r == 0 || r == rows - 1) && (c == 0 || c == columns - 1))
+}
+
+fn direction_name(direction: Compass) -> &'static str {
+    match direction {
+        Compass::North => "north",
+        Compass::East => "east",
+        Compass::South => "south",
+        Compass::West => "west",
+    }
+}
+
+fn parse_direction(name: &str) -> Result<Compass> {
+    Compass::all()
+        .into_iter()
+        .find(|d| direction_name(*d) == name)
+        .ok_or(Error::InvalidFormat("unknown direction"))
+}
+
+#[allow(dead_code)]
+impl MazeSpec {
+    /// Spec of a sidewinder maze carved north and east with seed 0
+    pub fn new(rows: u32, columns: u32) -> Self {
+        MazeSpec {
+            grid_type: GridType::Grid,
+            rows,
+            columns,
+            mask: Mask::None,
+            router: RouterKind::SideWinder,
+            directions: (Compass::North, Compass::East),
+            seed: 0,
+        }
+    }
+
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    pub fn router(mut self, router: RouterKind) -> Self {
+        self.router = router;
+        self
+    }
+
+    /// Directions carved towards, for a sidewinder the first closes each run
+    pub fn directions(mut self, directions: (Compass, Compass)) -> Self {
+        self.directions = directions;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Return a new random number generator for the seed
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed)
+    }
+
+    /// Check the spec describes a maze with at least one cell that the router can carve
+    pub fn validate(&self) -> Result<()> {
+        if self.rows == 0 || self.columns == 0 {
+            return Err(Error::InvalidFormat("size must be rows x columns"));
+        }
+        let (first, second) = self.directions;
+        if second != first.clockwise() && second != first.anticlockwise() {
+            return Err(Error::InvalidFormat("directions must be at right angles"));
+        }
+        if self.mask == Mask::Corners && self.rows <= 2 && self.columns <= 2 {
+            return Err(Error::InvalidFormat("mask leaves no cells"));
+        }
+        Ok(())
+    }
+
+    /// Carve the maze
+    pub fn build(&self) -> Result<Grid> {
+        self.validate()?;
+        Ok(self.carve(false))
+    }
+
+    /// Carve the maze, recording the history for animation
+    pub fn recorded(&self) -> Result<Grid> {
+        self.validate()?;
+        Ok(self.carve(true))
+    }
+
+    fn carve(&self, recorded: bool) -> Grid {
+        let mut rng = self.rng();
+        let (first, second) = self.directions;
+        let mut router: Box<dyn Router<Compass, Grid>> = match self.router {
+            RouterKind::BinaryTree => Box::new(BinaryTree::new(&mut rng, vec![first, second])),
+            RouterKind::SideWinder => Box::new(SideWinder::new(&mut rng, (first, second))),
+        };
+        let allowed = |r, c| match self.mask {
+            Mask::None => true,
+            Mask::Corners => mask_corners(self.rows, self.columns)(r, c),
+        };
+        let mut grid = if recorded {
+            Grid::recorded(self.rows, self.columns, allowed, router.as_mut())
+        } else {
+            Grid::grid(self.rows, self.columns, allowed, router.as_mut())
+        };
+        // the router holds the rng, release it so the same sequence picks the joining walls
+        drop(router);
+        MazeSpec::connect(&mut grid, &mut rng);
+        grid
+    }
+
+    /// Link regions cut off by the mask to the cells reachable from the first cell, one wall
+    /// at a time, so the maze stays perfect
+    fn connect(grid: &mut Grid, rng: &mut ChaCha8Rng) {
+        let first = match grid.cells().first() {
+            Some(cell) => **cell,
+            None => return,
+        };
+        loop {
+            let regions = breadth_first(grid, &[first]);
+            let reached = regions.distances();
+            let walls: Vec<(Cell, Compass)> = grid
+                .cells()
+                .into_iter()
+                .filter(|c| reached.is_reachable(**c))
+                .flat_map(|c| Compass::all().into_iter().map(move |d| (*c, d)))
+                .filter(|(c, d)| {
+                    grid.neighbours(c)
+                        .get(d)
+                        .is_some_and(|n| !reached.is_reachable(*n))
+                })
+                .collect();
+            if walls.is_empty() {
+                return;
+            }
+            let (cell, direction) = walls[rng.gen::<u32>() as usize % walls.len()];
+            grid.link_cell(&cell, direction);
+        }
+    }
+}
+
+impl fmt::Display for MazeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let grid_type = match self.grid_type {
+            GridType::Grid => "grid",
+        };
+        let router = match self.router {
+            RouterKind::BinaryTree => "binarytree",
+            RouterKind::SideWinder => "sidewinder",
+        };
+        write!(f, "{}:{}x{}:{}", grid_type, self.rows, self.columns, router)?;
+        if self.mask == Mask::Corners {
+            f.write_str(":mask=corners")?;
+        }
+        if self.directions != (Compass::North, Compass::East) {
+            write!(
+                f,
+                ":directions={},{}",
+                direction_name(self.directions.0),
+                direction_name(self.directions.1)
+            )?;
+        }
+        write!(f, ":seed={}", self.seed)
+    }
+}
+
+impl FromStr for MazeSpec {
+    type Err = Error;
+
+    /// Read a spec written by `to_string`, options may be in any order
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.trim().split(':');
+        match parts.next() {
+            Some("grid") => {}
+            _ => return Err(Error::InvalidFormat("unknown grid type")),
+        }
+        let (rows, columns) = parts
+            .next()
+            .and_then(|size| size.split_once('x'))
+            .and_then(|(r, c)| Some((r.parse::<u32>().ok()?, c.parse::<u32>().ok()?)))
+            .ok_or(Error::InvalidFormat("size must be rows x columns"))?;
+        let router = match parts.next() {
+            Some("binarytree") => RouterKind::BinaryTree,
+            Some("sidewinder") => RouterKind::SideWinder,
+            _ => return Err(Error::InvalidFormat("unknown router")),
+        };
+
+        let mut spec = MazeSpec::new(rows, columns).router(router);
+        for option in parts {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or(Error::InvalidFormat("options must be key=value"))?;
+            match key {
+                "seed" => {
+                    spec.seed = value
+                        .parse()
+                        .map_err(|_| Error::InvalidFormat("invalid seed"))?
+                }
+                "mask" => {
+                    spec.mask = match value {
+                        "none" => Mask::None,
+                        "corners" => Mask::Corners,
+                        _ => return Err(Error::InvalidFormat("unknown mask")),
+                    }
+                }
+                "directions" => {
+                    let (first, second) = value
+                        .split_once(',')
+                        .ok_or(Error::InvalidFormat("directions must be two names"))?;
+                    spec.directions = (parse_direction(first)?, parse_direction(second)?);
+                }
+                _ => return Err(Error::InvalidFormat("unknown option")),
+            }
+        }
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::dijkstra::Dijkstra;
+
+    #[test]
+    fn check_mask_corners() {
+        let f = mask_corners(5, 5);
+
+        assert!(!f(0, 0));
+        assert!(f(0, 2));
+        assert!(!f(0, 4));
+        assert!(f(2, 2));
+        assert!(!f(4, 0));
+        assert!(f(4, 2));
+        assert!(!f(4, 4));
+    }
+
+    #[test]
+    fn check_parse() {
+        let spec: MazeSpec = "grid:20x30:binarytree:seed=42:mask=corners:directions=south,west"
+            .parse()
+            .expect("Could not parse spec");
+
+        assert_eq!(
+            spec,
+            MazeSpec::new(20, 30)
+                .router(RouterKind::BinaryTree)
+                .mask(Mask::Corners)
+                .directions((Compass::South, Compass::West))
+                .seed(42)
+        );
+        assert_eq!(
+            spec.to_string(),
+            "grid:20x30:binarytree:mask=corners:directions=south,west:seed=42"
+        );
+        assert_eq!(
+            MazeSpec::new(20, 20).seed(42).to_string(),
+            "grid:20x20:sidewinder:seed=42"
+        );
+    }
+
+    #[test]
+    fn check_parse_invalid() {
+        for (spec, reason) in [
+            ("hex:2x2:sidewinder", "unknown grid type"),
+            ("grid:2by2:sidewinder", "size must be rows x columns"),
+            ("grid:0x2:sidewinder", "size must be rows x columns"),
+            ("grid:2x2:aldous", "unknown router"),
+            ("grid:2x2:sidewinder:seed", "options must be key=value"),
+            ("grid:2x2:sidewinder:seed=-1", "invalid seed"),
+            ("grid:2x2:sidewinder:mask=circle", "unknown mask"),
+            (
+                "grid:2x2:sidewinder:directions=north",
+                "directions must be two names",
+            ),
+            (
+                "grid:2x2:sidewinder:directions=north,up",
+                "unknown direction",
+            ),
+            (
+                "grid:2x2:sidewinder:directions=north,south",
+                "directions must be at right angles",
+            ),
+            ("grid:2x2:sidewinder:colour=red", "unknown option"),
+            ("grid:2x2:sidewinder:mask=corners", "mask leaves no cells"),
+            ("grid:1x2:binarytree:mask=corners", "mask leaves no cells"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                spec.parse::<MazeSpec>().unwrap_err(),
+                Error::InvalidFormat(reason),
+                "{}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn check_reproducible() {
+        let spec: MazeSpec = "grid:8x8:sidewinder:seed=42"
+            .parse()
+            .expect("Could not parse spec");
+
+        let grid = spec.build().expect("Could not build maze");
+
+        assert_eq!(grid.to_string(), spec.build().unwrap().to_string());
+        assert_eq!(grid.to_string(), spec.recorded().unwrap().to_string());
+        assert_ne!(grid.to_string(), spec.seed(43).build().unwrap().to_string());
+    }
+
+    #[test]
+    fn check_build_invalid() {
+        // the builder is checked the same way as a parsed spec
+        for (spec, reason) in [
+            (MazeSpec::new(0, 3), "size must be rows x columns"),
+            (
+                MazeSpec::new(3, 3).directions((Compass::North, Compass::South)),
+                "directions must be at right angles",
+            ),
+            (
+                MazeSpec::new(0, 2).mask(Mask::Corners),
+                "size must be rows x columns",
+            ),
+            (
+                MazeSpec::new(2, 2).mask(Mask::Corners),
+                "mask leaves no cells",
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(spec.build().unwrap_err(), Error::InvalidFormat(reason));
+            assert_eq!(spec.recorded().unwrap_err(), Error::InvalidFormat(reason));
+        }
+        assert!(MazeSpec::new(1, 3).mask(Mask::Corners).build().is_ok());
+    }
+
+    #[test]
+    fn check_mask() {
+        let grid = MazeSpec::new(3, 4)
+            .mask(Mask::Corners)
+            .build()
+            .expect("Could not build maze");
+
+        assert_eq!(grid.cells().len(), 8);
+        assert!(grid.cell(0, 0).is_none());
+        assert!(grid.cell(2, 3).is_none());
+    }
+
+    #[test]
+    fn check_mask_connected() {
+        for router in [RouterKind::BinaryTree, RouterKind::SideWinder].iter() {
+            for seed in 0..50 {
+                for (rows, columns) in [(5, 5), (6, 6), (2, 7), (7, 3)].iter() {
+                    let spec = MazeSpec::new(*rows, *columns)
+                        .router(*router)
+                        .mask(Mask::Corners)
+                        .seed(seed);
+                    let grid = spec.build().expect("Could not build maze");
+                    let start = grid.cells()[0].coords();
+
+                    let distances = Dijkstra::solve(&grid, start).expect("Could not solve maze");
+
+                    assert_eq!(distances.all_cells().len(), grid.cells().len(), "{}", spec);
+                    // joining regions one wall at a time keeps the maze free of loops
+                    let links: usize = grid.cells().iter().map(|c| grid.links(c).len()).sum();
+                    assert_eq!(links / 2, grid.cells().len() - 1, "{}", spec);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_fixed_output() {
+        // these mazes must never change, or saved specs would carve something else
+        let newline = String::from("\n");
+        let grid = MazeSpec::new(4, 4).seed(42).build().unwrap();
+        let binary = MazeSpec::new(3, 4)
+            .router(RouterKind::BinaryTree)
+            .directions((Compass::South, Compass::West))
+            .seed(7)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            newline.clone() + &grid.to_string(),
+            r#"
++---+---+---+---+
+|               |
++   +---+   +   +
+|       |   |   |
++   +---+   +   +
+|       |   |   |
++---+   +   +   +
+|       |   |   |
++---+---+---+---+
+"#
+        );
+        assert_eq!(
+            newline + &binary.to_string(),
+            r#"
++---+---+---+---+
+|       |   |   |
++   +---+   +   +
+|               |
++   +---+---+---+
+|               |
++---+---+---+---+
+"#
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::util::colourmap::ColourMap;
+use crate::util::image::{BLACK, BLUE, GREY, RED, SILVER, WHITE};
+
+use image::Rgb;
+
+/// Sizes and colours shared by the PNG and SVG renderers
+///
+/// Build a custom style from one of the presets, e.g.
+/// ```
+///     let style = RenderStyle {
+///         cell_size: 20,
+///         ..RenderStyle::print()
+///     };
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenderStyle {
+    /// width and height of each cell, including one wall, at least 2 when drawn as pixels
+    pub cell_size: u32,
+    /// drawn as pixels between 1 and one less than the cell size
+    pub wall_thickness: u32,
+    /// space between the edge of the image and the outer wall
+    pub margin: u32,
+    pub background: Rgb<u8>,
+    pub cell: Rgb<u8>,
+    pub wall: Rgb<u8>,
+    pub masked: Rgb<u8>,
+    pub filled: Rgb<u8>,
+    pub solution: Rgb<u8>,
+    /// colours used from the nearest to the furthest distances
+    pub gradient: ColourMap,
+    /// fraction of the cell size left solid on each side, drawing passages as corridors
+    /// instead of thin walls, 0 draws the classic walls and anything from 0.5 is treated as 0.45
+    pub inset: f32,
+    /// smooth edges in SVG output, PNG renderers always draw sharp edges
+    pub antialias: bool,
+}
+
+impl Default for RenderStyle {
+    fn default() -> Self {
+        RenderStyle {
+            cell_size: 10,
+            wall_thickness: 1,
+            margin: 10,
+            background: GREY,
+            cell: WHITE,
+            wall: BLACK,
+            masked: BLACK,
+            filled: SILVER,
+            solution: RED,
+            gradient: ColourMap::linear(WHITE, BLUE),
+            inset: 0.0,
+            antialias: true,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl RenderStyle {
+    /// Larger cells on a white page, using shades of grey to save ink
+    pub fn print() -> Self {
+        RenderStyle {
+            cell_size: 20,
+            wall_thickness: 2,
+            margin: 20,
+            background: WHITE,
+            masked: Rgb([224u8, 224u8, 224u8]),
+            solution: BLACK,
+            gradient: ColourMap::linear(WHITE, GREY),
+            ..RenderStyle::default()
+        }
+    }
+
+    /// Light walls on a dark background
+    pub fn dark() -> Self {
+        RenderStyle {
+            background: Rgb([24u8, 24u8, 24u8]),
+            cell: Rgb([48u8, 48u8, 48u8]),
+            wall: Rgb([224u8, 224u8, 224u8]),
+            masked: Rgb([0u8, 0u8, 0u8]),
+            filled: Rgb([88u8, 88u8, 88u8]),
+            solution: Rgb([255u8, 192u8, 0u8]),
+            gradient: ColourMap::linear(Rgb([48u8, 48u8, 48u8]), Rgb([0u8, 160u8, 255u8])),
+            ..RenderStyle::default()
+        }
+    }
+
+    /// Thick black walls and sharp edges for low vision readers
+    pub fn high_contrast() -> Self {
+        RenderStyle {
+            cell_size: 16,
+            wall_thickness: 3,
+            margin: 16,
+            background: WHITE,
+            filled: GREY,
+            gradient: ColourMap::linear(WHITE, Rgb([0u8, 0u8, 160u8])),
+            antialias: false,
+            ..RenderStyle::default()
+        }
+    }
+
+    /// Corridors a third of the cell wide, with the walls drawn around the corridor edges,
+    /// suited to laser cutting and 3D printing
+    pub fn inset() -> Self {
+        RenderStyle {
+            cell_size: 20,
+            wall_thickness: 1,
+            margin: 20,
+            background: WHITE,
+            masked: WHITE,
+            inset: 1.0 / 3.0,
+            ..RenderStyle::default()
+        }
+    }
+
+    /// Return the inset as a distance from the cell edge, clamped to leave a visible corridor
+    pub fn inset_size(&self) -> f32 {
+        self.pixel_sizes().0 as f32 * self.inset.clamp(0.0, 0.45)
+    }
+
+    /// Return the cell size and wall thickness in whole pixels, clamped so that every cell
+    /// keeps at least one pixel of wall and one of space
+    pub fn pixel_sizes(&self) -> (u32, u32) {
+        let size = self.cell_size.max(2);
+        (size, self.wall_thickness.clamp(1, size - 1))
+    }
+
+    /// Return the image width and height needed for a maze of the given size
+    pub fn image_size(&self, rows: u32, columns: u32) -> (u32, u32) {
+        let (size, _) = self.pixel_sizes();
+        (
+            size * columns + 2 * self.margin,
+            size * rows + 2 * self.margin,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_image_size() {
+        assert_eq!(RenderStyle::default().image_size(5, 3), (50, 70));
+        assert_eq!(RenderStyle::print().image_size(5, 3), (100, 140));
+    }
+
+    #[test]
+    fn check_pixel_sizes() {
+        assert_eq!(RenderStyle::print().pixel_sizes(), (20, 2));
+        let style = RenderStyle {
+            cell_size: 0,
+            wall_thickness: 0,
+            ..RenderStyle::default()
+        };
+        assert_eq!(style.pixel_sizes(), (2, 1));
+        let style = RenderStyle {
+            cell_size: 4,
+            wall_thickness: 9,
+            ..RenderStyle::default()
+        };
+        assert_eq!(style.pixel_sizes(), (4, 3));
+    }
+
+    #[test]
+    fn check_presets() {
+        assert_eq!(RenderStyle::print().background, WHITE);
+        assert_eq!(RenderStyle::dark().cell_size, 10);
+        assert!(!RenderStyle::high_contrast().antialias);
+    }
+
+    #[test]
+    fn check_inset_size() {
+        assert_eq!(RenderStyle::default().inset_size(), 0.0);
+        assert_eq!(
+            RenderStyle {
+                inset: 0.25,
+                ..RenderStyle::default()
+            }
+            .inset_size(),
+            2.5
+        );
+        assert_eq!(
+            RenderStyle {
+                inset: 0.9,
+                ..RenderStyle::default()
+            }
+            .inset_size(),
+            4.5
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::maze::grid::Grid;
+use crate::maze::style::RenderStyle;
+use crate::maze::unicode::Unicode;
+use crate::maze::Cell;
+
+use image::Rgb;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+/// Console renderer that colours cells with 24-bit ANSI escapes, built on the Unicode renderer
+///
+/// Colours are only used when stdout is a terminal, otherwise the markers are printed as
+/// plain text.
+pub struct Terminal<'a> {
+    text: Unicode<'a>,
+    grid: &'a Grid,
+    style: RenderStyle,
+    path: HashSet<Cell>,
+    start: Option<Cell>,
+    goal: Option<Cell>,
+    colour: bool,
+}
+
+#[allow(dead_code)]
+impl<'a> Terminal<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Terminal {
+            text: Unicode::new(grid),
+            grid,
+            style: RenderStyle::default(),
+            path: HashSet::new(),
+            start: None,
+            goal: None,
+            colour: io::stdout().is_terminal(),
+        }
+    }
+
+    /// Set the colours used for the distance gradient and solution
+    pub fn style(mut self, style: RenderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the number of characters used for the inside of each cell
+    pub fn width(mut self, width: usize) -> Self {
+        self.text = self.text.width(width);
+        self
+    }
+
+    /// Mark the cells of a solution path
+    pub fn solution(mut self, path: &[Cell]) -> Self {
+        self.path = path.iter().copied().collect();
+        self
+    }
+
+    pub fn start(mut self, cell: Cell) -> Self {
+        self.start = Some(cell);
+        self
+    }
+
+    pub fn goal(mut self, cell: Cell) -> Self {
+        self.goal = Some(cell);
+        self
+    }
+
+    /// Force colours on or off, overriding the terminal detection
+    pub fn colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    /// Return the marker for a cell, or None to show its label
+    fn marker(&self, cell: &Cell) -> Option<&str> {
+        if Some(*cell) == self.start {
+            Some("S")
+        } else if Some(*cell) == self.goal {
+            Some("G")
+        } else if self.path.contains(cell) {
+            Some("•")
+        } else {
+            None
+        }
+    }
+
+    fn paint(&self, cell: &Cell, body: String) -> String {
+        let marked = self
+            .marker(cell)
+            .map(|m| Unicode::centre(m, body.chars().count()));
+
+        if !self.colour {
+            return marked.unwrap_or(body);
+        }
+        let background = self.grid.cell_colour(cell, &self.style);
+        let foreground = if marked.is_some() {
+            self.style.solution
+        } else {
+            contrast(background)
+        };
+        format!(
+            "\x1b[48;2;{};{};{}m\x1b[38;2;{};{};{}m{}\x1b[0m",
+            background[0],
+            background[1],
+            background[2],
+            foreground[0],
+            foreground[1],
+            foreground[2],
+            marked.unwrap_or(body)
+        )
+    }
+}
+
+/// Return black or white, whichever is easier to read on the background
+fn contrast(background: Rgb<u8>) -> Rgb<u8> {
+    let luma =
+        0.299 * background[0] as f32 + 0.587 * background[1] as f32 + 0.114 * background[2] as f32;
+
+    if luma > 128.0 {
+        Rgb([0u8, 0u8, 0u8])
+    } else {
+        Rgb([255u8, 255u8, 255u8])
+    }
+}
+
+impl<'a> fmt::Display for Terminal<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text.render(|cell, body| self.paint(cell, body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::Compass;
+    use crate::maze::Maze;
+    use crate::router::internal::NoOp;
+
+    fn corridor() -> Grid {
+        let mut grid = Grid::grid(1, 3, Grid::ALLOW_ALL, &mut NoOp {});
+        for column in 0..2 {
+            let cell = *grid.cell(0, column).expect("Missing Cell");
+            grid.link_cell(&cell, Compass::East);
+        }
+        grid
+    }
+
+    #[test]
+    fn check_terminal_plain() {
+        let grid = corridor();
+        let path: Vec<Cell> = grid.cells().into_iter().copied().collect();
+
+        assert_eq!(
+            format!(
+                "\n{}",
+                Terminal::new(&grid)
+                    .colour(false)
+                    .solution(&path)
+                    .start(path[0])
+                    .goal(path[2])
+            ),
+            r#"
+┌───────────┐
+│ S   •   G │
+└───────────┘
+"#
+        );
+    }
+
+    #[test]
+    fn check_terminal_colour() {
+        let grid = corridor();
+        let start = *grid.cell(0, 0).expect("Missing Cell 0,0");
+
+        let text = Terminal::new(&grid).colour(true).start(start).to_string();
+
+        assert!(text.contains("\x1b[48;2;255;255;255m\x1b[38;2;255;0;0m S \x1b[0m"));
+        assert!(text.contains("\x1b[48;2;255;255;255m\x1b[38;2;0;0;0m   \x1b[0m"));
+    }
+
+    #[test]
+    fn check_contrast() {
+        assert_eq!(contrast(Rgb([255u8, 255u8, 255u8])), Rgb([0u8, 0u8, 0u8]));
+        assert_eq!(contrast(Rgb([0u8, 0u8, 255u8])), Rgb([255u8, 255u8, 255u8]));
+    }
+}
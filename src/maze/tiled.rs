@@ -0,0 +1,343 @@
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::internal::MazeAccessor;
+use crate::maze::style::RenderStyle;
+use crate::maze::{Cell, Maze};
+use crate::util::svg::escape;
+
+use image::{ImageError, ImageResult, RgbImage};
+use imageproc::{drawing, rect};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Bit set in the tile id for each open side of a cell
+const OPEN: [(Compass, u32, &str); 4] = [
+    (Compass::North, 1, "north"),
+    (Compass::East, 2, "east"),
+    (Compass::South, 4, "south"),
+    (Compass::West, 8, "west"),
+];
+/// One tile for every combination of open sides
+pub const TILES: u32 = 16;
+
+/// Tiled map of a grid, for loading mazes as game levels
+///
+/// Each cell is a tile chosen by which sides are open, the tile id has bit 1 set for north,
+/// 2 for east, 4 for south and 8 for west, and masked cells are left empty. The tileset
+/// describes the same sides as boolean tile properties so games need not decode the ids.
+/// An object layer marks the start, the goal and the applied distance of every cell.
+/// ```
+///     Tiled::new(&grid).start(start).goal(goal).write("levels/maze.tmx")?;
+/// ```
+pub struct Tiled<'a> {
+    grid: &'a Grid,
+    tile_size: u32,
+    style: RenderStyle,
+    start: Option<Cell>,
+    goal: Option<Cell>,
+}
+
+#[allow(dead_code)]
+impl<'a> Tiled<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Tiled {
+            grid,
+            tile_size: 16,
+            style: RenderStyle::default(),
+            start: None,
+            goal: None,
+        }
+    }
+
+    /// Width and height of each tile in pixels
+    pub fn tile_size(mut self, size: u32) -> Self {
+        self.tile_size = size.max(3);
+        self
+    }
+
+    /// Colours of the floor and walls in the tileset image
+    pub fn style(mut self, style: RenderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn start(mut self, cell: Cell) -> Self {
+        self.start = Some(cell);
+        self
+    }
+
+    pub fn goal(mut self, cell: Cell) -> Self {
+        self.goal = Some(cell);
+        self
+    }
+
+    /// Return the id of the tile for a cell within the tileset
+    pub fn tile(&self, cell: &Cell) -> u32 {
+        let links = self.grid.links(cell);
+        OPEN.iter()
+            .filter(|(direction, _, _)| links.contains(direction))
+            .map(|(_, bit, _)| bit)
+            .sum()
+    }
+
+    /// Return the map in TMX format, using the tileset in the named `.tsx` file
+    pub fn tmx(&self, tileset: &str) -> String {
+        let (rows, columns) = self.grid.size();
+        let size = self.tile_size;
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        let mut objects = String::new();
+        let mut id = 1;
+        let mut object = |name: &str, cell: &Cell| {
+            let _ = writeln!(
+                objects,
+                "  <object id=\"{}\" name=\"{}\" type=\"{}\" x=\"{}\" y=\"{}\">",
+                id,
+                name,
+                name,
+                (cell.column() * size) as f32 + size as f32 / 2.0,
+                (cell.row() * size) as f32 + size as f32 / 2.0
+            );
+            let _ = writeln!(
+                objects,
+                "   <properties>\n    <property name=\"row\" type=\"int\" value=\"{}\"/>\n    <property name=\"column\" type=\"int\" value=\"{}\"/>",
+                cell.row(),
+                cell.column()
+            );
+            if let Some(distance) = self.grid._attributes(cell).distance() {
+                let _ = writeln!(
+                    objects,
+                    "    <property name=\"distance\" type=\"int\" value=\"{}\"/>",
+                    distance
+                );
+            }
+            objects.push_str("   </properties>\n   <point/>\n  </object>\n");
+            id += 1;
+        };
+        for (name, cell) in [("start", self.start), ("goal", self.goal)].iter() {
+            if let Some(cell) = cell {
+                object(name, cell);
+            }
+        }
+        for cell in self.grid.cells() {
+            if self.grid._attributes(cell).distance().is_some() {
+                object("distance", cell);
+            }
+        }
+
+        let _ = writeln!(
+            s,
+            "<map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"0\" nextlayerid=\"3\" nextobjectid=\"{}\">",
+            columns, rows, size, size, id
+        );
+        let _ = writeln!(
+            s,
+            " <tileset firstgid=\"1\" source=\"{}\"/>",
+            escape(tileset)
+        );
+        let _ = writeln!(
+            s,
+            " <layer id=\"1\" name=\"maze\" width=\"{}\" height=\"{}\">",
+            columns, rows
+        );
+        s.push_str("  <data encoding=\"csv\">\n");
+        for row in 0..rows {
+            let line: Vec<String> = (0..columns)
+                .map(|column| match self.grid.cell(row, column) {
+                    // global ids count from one, zero is no tile
+                    Some(cell) => (self.tile(cell) + 1).to_string(),
+                    None => "0".to_string(),
+                })
+                .collect();
+            s.push_str(&line.join(","));
+            s.push_str(if row + 1 < rows { ",\n" } else { "\n" });
+        }
+        s.push_str("  </data>\n </layer>\n");
+        s.push_str(" <objectgroup id=\"2\" name=\"markers\">\n");
+        s.push_str(&objects);
+        s.push_str(" </objectgroup>\n</map>\n");
+        s
+    }
+
+    /// Return the tileset in TSX format, using the tiles in the named image
+    pub fn tsx(&self, image: &str) -> String {
+        let size = self.tile_size;
+        let mut s = String::new();
+        s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            s,
+            "<tileset version=\"1.10\" name=\"maze\" tilewidth=\"{}\" tileheight=\"{}\" tilecount=\"{}\" columns=\"{}\">",
+            size, size, TILES, TILES
+        );
+        let _ = writeln!(
+            s,
+            " <image source=\"{}\" width=\"{}\" height=\"{}\"/>",
+            escape(image),
+            size * TILES,
+            size
+        );
+        for tile in 0..TILES {
+            let _ = writeln!(s, " <tile id=\"{}\">\n  <properties>", tile);
+            for (_, bit, name) in OPEN.iter() {
+                let _ = writeln!(
+                    s,
+                    "   <property name=\"{}\" type=\"bool\" value=\"{}\"/>",
+                    name,
+                    tile & bit != 0
+                );
+            }
+            s.push_str("  </properties>\n </tile>\n");
+        }
+        s.push_str("</tileset>\n");
+        s
+    }
+
+    /// Return the tileset image, every tile in one row in order of id
+    pub fn tileset_image(&self) -> RgbImage {
+        let size = self.tile_size;
+        let wall = (size / 8).max(1);
+        let mut image = RgbImage::from_pixel(size * TILES, size, self.style.cell);
+        let mut fill = |x: u32, y: u32, w: u32, h: u32| {
+            drawing::draw_filled_rect_mut(
+                &mut image,
+                rect::Rect::at(x as i32, y as i32).of_size(w, h),
+                self.style.wall,
+            )
+        };
+
+        for tile in 0..TILES {
+            let x = tile * size;
+            let far = size - wall;
+            // corners always stand so that walls of neighbouring tiles join up
+            for (cx, cy) in [(0, 0), (far, 0), (0, far), (far, far)].iter() {
+                fill(x + cx, *cy, wall, wall);
+            }
+            if tile & 1 == 0 {
+                fill(x, 0, size, wall);
+            }
+            if tile & 2 == 0 {
+                fill(x + far, 0, wall, size);
+            }
+            if tile & 4 == 0 {
+                fill(x, far, size, wall);
+            }
+            if tile & 8 == 0 {
+                fill(x, 0, wall, size);
+            }
+        }
+        image
+    }
+
+    /// Write the map, with the tileset and its image alongside as `.tsx` and `.png` files of
+    /// the same name
+    pub fn write(&self, filename: &str) -> ImageResult<()> {
+        let path = Path::new(filename);
+        let tsx = path.with_extension("tsx");
+        let png = path.with_extension("png");
+        let name = |p: &Path| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        self.tileset_image().save(&png)?;
+        fs::write(&tsx, self.tsx(&name(&png))).map_err(ImageError::IoError)?;
+        fs::write(path, self.tmx(&name(&tsx))).map_err(ImageError::IoError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::internal::NoOp;
+    use crate::solver::dijkstra::Dijkstra;
+    use crate::util::image::{BLACK, WHITE};
+
+    fn maze() -> Grid {
+        let mut grid = Grid::grid(2, 2, |r, c| r != 1 || c != 1, &mut NoOp {});
+        let cell = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        grid.link_cell(&cell, Compass::South);
+        grid
+    }
+
+    #[test]
+    fn check_tiles() {
+        let grid = maze();
+        let tiled = Tiled::new(&grid);
+
+        let cell_00 = grid.cell(0, 0).expect("Missing Cell 0,0");
+        let cell_01 = grid.cell(0, 1).expect("Missing Cell 0,1");
+        let cell_10 = grid.cell(1, 0).expect("Missing Cell 1,0");
+        assert_eq!(tiled.tile(cell_00), 2 | 4);
+        assert_eq!(tiled.tile(cell_01), 8);
+        assert_eq!(tiled.tile(cell_10), 1);
+    }
+
+    #[test]
+    fn check_tmx() {
+        let grid = maze();
+
+        let tmx = Tiled::new(&grid).tmx("maze.tsx");
+
+        assert!(tmx.contains(r#"width="2" height="2" tilewidth="16" tileheight="16""#));
+        assert!(tmx.contains(r#"<tileset firstgid="1" source="maze.tsx"/>"#));
+        assert!(tmx.contains("<data encoding=\"csv\">\n7,9,\n2,0\n  </data>"));
+        assert!(tmx.contains("nextobjectid=\"1\""));
+        assert!(!tmx.contains("<object "));
+    }
+
+    #[test]
+    fn check_objects() {
+        let mut grid = maze();
+        grid.apply_distances(Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze"));
+        let start = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        let goal = *grid.cell(1, 0).expect("Missing Cell 1,0");
+
+        let tmx = Tiled::new(&grid).start(start).goal(goal).tmx("maze.tsx");
+
+        assert!(tmx.contains(
+            "  <object id=\"1\" name=\"start\" type=\"start\" x=\"8\" y=\"8\">\n   <properties>\n    <property name=\"row\" type=\"int\" value=\"0\"/>"
+        ));
+        assert!(tmx.contains(r#"<object id="2" name="goal" type="goal" x="8" y="24">"#));
+        assert!(tmx.contains(r#"<property name="distance" type="int" value="1"/>"#));
+        // start and goal plus a distance for each of the three cells
+        assert_eq!(tmx.matches("<object ").count(), 5);
+        assert!(tmx.contains("nextobjectid=\"6\""));
+    }
+
+    #[test]
+    fn check_tsx() {
+        let grid = maze();
+
+        let tsx = Tiled::new(&grid).tile_size(8).tsx("maze.png");
+
+        assert!(tsx.contains(r#"tilewidth="8" tileheight="8" tilecount="16" columns="16">"#));
+        assert!(tsx.contains(r#"<image source="maze.png" width="128" height="8"/>"#));
+        assert_eq!(tsx.matches("<tile ").count(), 16);
+        assert!(tsx.contains(
+            " <tile id=\"5\">\n  <properties>\n   <property name=\"north\" type=\"bool\" value=\"true\"/>\n   <property name=\"east\" type=\"bool\" value=\"false\"/>\n   <property name=\"south\" type=\"bool\" value=\"true\"/>"
+        ));
+    }
+
+    #[test]
+    fn check_tileset_image() {
+        let grid = maze();
+        let style = RenderStyle {
+            cell: WHITE,
+            wall: BLACK,
+            ..RenderStyle::default()
+        };
+
+        let image = Tiled::new(&grid).tile_size(8).style(style).tileset_image();
+
+        assert_eq!(image.dimensions(), (128, 8));
+        // tile 0 is closed on every side, tile 15 only has its corners
+        assert_eq!(*image.get_pixel(4, 0), BLACK);
+        assert_eq!(*image.get_pixel(0, 4), BLACK);
+        assert_eq!(*image.get_pixel(4, 4), WHITE);
+        assert_eq!(*image.get_pixel(15 * 8 + 4, 0), WHITE);
+        assert_eq!(*image.get_pixel(15 * 8, 0), BLACK);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::maze::grid::{Compass, Grid};
+use crate::maze::internal::MazeAccessor;
+use crate::maze::{Cell, Maze};
+
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Text renderer for a grid using Unicode box drawing characters
+///
+/// Cells show their applied distance by default, or any labels supplied.
+/// ```
+///     print!("{}", Unicode::new(&grid).width(5));
+/// ```
+pub struct Unicode<'a> {
+    grid: &'a Grid,
+    width: usize,
+    labels: Option<HashMap<Cell, String>>,
+}
+
+#[allow(dead_code)]
+impl<'a> Unicode<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Unicode {
+            grid,
+            width: 3,
+            labels: None,
+        }
+    }
+
+    /// Set the least number of characters used for the inside of each cell, every cell is
+    /// widened to fit the longest label so nothing is cut short
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Show the given labels in place of the applied distances
+    pub fn labels(mut self, labels: HashMap<Cell, String>) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    fn label(&self, cell: &Cell) -> String {
+        match &self.labels {
+            Some(labels) => labels.get(cell).cloned().unwrap_or_default(),
+            None => self
+                .grid
+                ._attributes(cell)
+                .distance()
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Return true if there is a wall along the given side of the cell position, masked cells
+    /// are walled in while positions outside the grid have no walls of their own
+    fn wall(&self, row: i64, column: i64, direction: Compass) -> bool {
+        let (other_row, other_column) = match direction {
+            Compass::North => (row - 1, column),
+            Compass::West => (row, column - 1),
+            _ => unreachable!("Walls are only checked to the north and west"),
+        };
+        if !self.inside(row, column) && !self.inside(other_row, other_column) {
+            return false;
+        }
+        match self.cell(row, column) {
+            Some(c) => !self.grid.links(c).contains(&direction),
+            None => true,
+        }
+    }
+
+    fn inside(&self, row: i64, column: i64) -> bool {
+        row >= 0
+            && column >= 0
+            && row < self.grid.rows() as i64
+            && column < self.grid.columns() as i64
+    }
+
+    /// Return the cell at a position that may be outside the grid
+    fn cell(&self, row: i64, column: i64) -> Option<&Cell> {
+        if self.inside(row, column) {
+            self.grid.cell(row as u32, column as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Wall along the top of the cell at the row and column
+    fn horizontal(&self, row: i64, column: i64) -> bool {
+        self.wall(row, column, Compass::North)
+    }
+
+    /// Wall along the left of the cell at the row and column
+    fn vertical(&self, row: i64, column: i64) -> bool {
+        self.wall(row, column, Compass::West)
+    }
+
+    fn junction(&self, row: i64, column: i64) -> char {
+        let rows = self.grid.rows() as i64;
+        let columns = self.grid.columns() as i64;
+        let up = row > 0 && self.vertical(row - 1, column);
+        let right = column < columns && self.horizontal(row, column);
+        let down = row < rows && self.vertical(row, column);
+        let left = column > 0 && self.horizontal(row, column - 1);
+
+        match (up, right, down, left) {
+            (false, false, false, false) => ' ',
+            (true, false, false, false) => '╵',
+            (false, true, false, false) => '╶',
+            (false, false, true, false) => '╷',
+            (false, false, false, true) => '╴',
+            (true, false, true, false) => '│',
+            (false, true, false, true) => '─',
+            (false, true, true, false) => '┌',
+            (false, false, true, true) => '┐',
+            (true, true, false, false) => '└',
+            (true, false, false, true) => '┘',
+            (true, true, true, false) => '├',
+            (true, false, true, true) => '┤',
+            (false, true, true, true) => '┬',
+            (true, true, false, true) => '┴',
+            (true, true, true, true) => '┼',
+        }
+    }
+
+    /// Return the width of the inside of each cell, wide enough for the longest label
+    fn cell_width(&self) -> usize {
+        self.grid
+            .cells()
+            .into_iter()
+            .map(|c| self.label(c).chars().count())
+            .fold(self.width, cmp::max)
+    }
+
+    /// Return the label padded either side to fill the width, labels are never cut short
+    pub(super) fn centre(label: &str, width: usize) -> String {
+        let padding = width.saturating_sub(label.chars().count());
+        let left = padding / 2;
+
+        format!(
+            "{}{}{}",
+            " ".repeat(left),
+            label,
+            " ".repeat(padding - left)
+        )
+    }
+
+    /// Render the grid, passing each cell and its centred label through `paint`
+    pub(super) fn render<F>(&self, paint: F) -> String
+    where
+        F: Fn(&Cell, String) -> String,
+    {
+        const NONE: char = '█';
+        let rows = self.grid.rows() as i64;
+        let columns = self.grid.columns() as i64;
+        let width = self.cell_width();
+        let mut s = String::new();
+
+        for row in 0..=rows {
+            // write the corners and horizontal walls
+            for column in 0..=columns {
+                s.push(self.junction(row, column));
+                if column < columns {
+                    let ch = if self.horizontal(row, column) {
+                        '─'
+                    } else {
+                        ' '
+                    };
+                    s.extend(std::iter::repeat_n(ch, width));
+                }
+            }
+            s.push('\n');
+            if row == rows {
+                break;
+            }
+            // write the cell bodies and vertical walls
+            for column in 0..=columns {
+                s.push(if self.vertical(row, column) {
+                    '│'
+                } else {
+                    ' '
+                });
+                if column < columns {
+                    match self.cell(row, column) {
+                        Some(cell) => {
+                            s.push_str(&paint(cell, Unicode::centre(&self.label(cell), width)))
+                        }
+                        None => s.extend(std::iter::repeat_n(NONE, width)),
+                    }
+                }
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<'a> fmt::Display for Unicode<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|_, body| body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::internal::NoOp;
+    use crate::solver::Distances;
+
+    #[test]
+    fn check_unicode_linked() {
+        let mut grid = Grid::square(2);
+        let cell_00 = *grid.cell(0, 0).expect("Missing Cell 0,0");
+        let cell_11 = *grid.cell(1, 1).expect("Missing Cell 1,1");
+        grid.link_cell(&cell_00, Compass::East);
+        grid.link_cell(&cell_11, Compass::North);
+
+        assert_eq!(
+            format!("\n{}", Unicode::new(&grid)),
+            r#"
+┌───────┐
+│       │
+├───┐   │
+│   │   │
+└───┴───┘
+"#
+        );
+    }
+
+    #[test]
+    fn check_unicode_masked() {
+        let grid = Grid::grid(2, 2, |r, c| r != 0 || c != 0, &mut NoOp {});
+
+        assert_eq!(
+            format!("\n{}", Unicode::new(&grid).width(1)),
+            r#"
+┌─┬─┐
+│█│ │
+├─┼─┤
+│ │ │
+└─┴─┘
+"#
+        );
+    }
+
+    #[test]
+    fn check_unicode_labels() {
+        let grid = Grid::grid(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let mut labels = HashMap::new();
+        labels.insert(
+            *grid.cell(0, 0).expect("Missing Cell 0,0"),
+            "100".to_string(),
+        );
+        labels.insert(
+            *grid.cell(0, 1).expect("Missing Cell 0,1"),
+            "123456".to_string(),
+        );
+
+        assert_eq!(
+            format!("\n{}", Unicode::new(&grid).width(5).labels(labels)),
+            r#"
+┌──────┬──────┐
+│ 100  │123456│
+└──────┴──────┘
+"#
+        );
+    }
+
+    #[test]
+    fn check_unicode_distances() {
+        let mut grid = Grid::grid(1, 40, Grid::ALLOW_ALL, &mut NoOp {});
+        for column in 0..39 {
+            let cell = *grid.cell(0, column).expect("Missing Cell");
+            grid.link_cell(&cell, Compass::East);
+        }
+        grid.apply_distances(
+            crate::solver::dijkstra::Dijkstra::solve(&grid, (0, 0)).expect("Could not solve"),
+        );
+
+        assert!(Unicode::new(&grid).to_string().contains(" 39 │"));
+    }
+
+    #[test]
+    fn check_unicode_wide_distances() {
+        let mut grid = Grid::grid(1, 2, Grid::ALLOW_ALL, &mut NoOp {});
+        let mut distances = HashMap::new();
+        distances.insert(*grid.cell(0, 0).expect("Missing Cell 0,0"), 0);
+        distances.insert(*grid.cell(0, 1).expect("Missing Cell 0,1"), 1234);
+        grid.apply_distances(Distances::new(distances));
+
+        assert_eq!(
+            format!("\n{}", Unicode::new(&grid)),
+            r#"
+┌────┬────┐
+│ 0  │1234│
+└────┴────┘
+"#
+        );
+    }
+}
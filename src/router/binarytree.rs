@@ -8,6 +8,7 @@ pub struct BinaryTree<'a, T: Direction> {
     preferred: Vec<T>,
 }
 
+#[allow(dead_code)]
 impl<'a, T: Direction> BinaryTree<'a, T> {
     pub fn new_for_compass(rng: &'a mut dyn RngCore) -> BinaryTree<'a, Compass> {
         BinaryTree::new(rng, vec![Compass::North, Compass::East])
@@ -28,7 +29,8 @@ impl<'a, T: Direction> BinaryTree<'a, T> {
         match directions.len() {
             0 => None,
             1 => Some(*directions[0]),
-            range => Some(*directions[self.rng.gen::<usize>() % range]),
+            // a u32 draws the same numbers on 32 and 64 bit targets, unlike usize
+            range => Some(*directions[self.rng.gen::<u32>() as usize % range]),
         }
     }
 }
@@ -7,10 +7,8 @@ pub trait Router<T: Direction, M: Maze<T>> {
     fn carve(&mut self, maze: &mut M, cells: Vec<Option<Cell>>);
 
     fn carve_by_cell(&mut self, maze: &mut M, cells: Vec<Option<Cell>>) {
-        for cell in cells {
-            if let Some(c) = cell {
-                self.by_cell(maze, c);
-            }
+        for c in cells.into_iter().flatten() {
+            self.by_cell(maze, c);
         }
     }
 
@@ -25,10 +23,8 @@ pub trait Router<T: Direction, M: Maze<T>> {
     }
 
     fn by_row(&mut self, maze: &mut M, cells: &[Option<Cell>], _row: u32) {
-        for cell in cells {
-            if let Some(c) = cell {
-                self.by_cell(maze, *c)
-            }
+        for c in cells.iter().flatten() {
+            self.by_cell(maze, *c)
         }
     }
 }
@@ -59,9 +55,9 @@ mod tests {
         let cell = grid.cell(0, 0).expect("Missing cell at 0,0");
         let links = grid.links(cell);
 
-        assert_eq!(links.contains(&Compass::North), false);
-        assert_eq!(links.contains(&Compass::East), false);
-        assert_eq!(links.contains(&Compass::South), false);
-        assert_eq!(links.contains(&Compass::West), false);
+        assert!(!links.contains(&Compass::North));
+        assert!(!links.contains(&Compass::East));
+        assert!(!links.contains(&Compass::South));
+        assert!(!links.contains(&Compass::West));
     }
 }
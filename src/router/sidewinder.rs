@@ -24,14 +24,15 @@ impl<'a, T: Direction> SideWinder<'a, T> {
     }
 
     fn close_row<M: Maze<T>>(&mut self, cell: &Cell, maze: &M, top: &T, side: &T) -> bool {
-        let neighbours = maze.neighbours(&cell);
+        let neighbours = maze.neighbours(cell);
 
         !neighbours.contains_key(side)
             || (neighbours.contains_key(top) && (self.rng.gen::<u16>() % 2 == 0))
     }
 
+    /// Pick from the run with a u32 so seeded mazes match across word sizes
     fn random_cell(&mut self) -> Cell {
-        self.run[self.rng.gen::<usize>() % self.run.len()]
+        self.run[self.rng.gen::<u32>() as usize % self.run.len()]
     }
 }
 
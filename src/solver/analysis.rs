@@ -0,0 +1,161 @@
+use crate::error::{Error, Result};
+use crate::maze::{Cell, Direction, Maze};
+use crate::solver::breadth_first;
+use std::cmp;
+use std::collections::HashMap;
+use std::thread;
+
+/// All-pairs distance metrics, searching breadth first from every cell in the maze so the
+/// distances are shortest paths in braided mazes with loops as well as perfect ones
+#[derive(Debug)]
+pub struct Analysis {
+    eccentricities: HashMap<Cell, u32>,
+}
+
+#[allow(dead_code)]
+impl Analysis {
+    /// Search from every cell, spreading the sources across the available threads
+    ///
+    /// Fails with `Unreachable` if the maze has disconnected regions, as no cell then has a
+    /// finite eccentricity.
+    pub fn new<T, M>(maze: &M) -> Result<Analysis>
+    where
+        T: Direction,
+        M: Maze<T> + Sync,
+    {
+        let cells: Vec<Cell> = maze.cells().into_iter().copied().collect();
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk = cmp::max(1, cells.len().div_ceil(threads));
+
+        let results: Vec<Result<Vec<(Cell, u32)>>> = thread::scope(|scope| {
+            let handles: Vec<_> = cells
+                .chunks(chunk)
+                .map(|sources| {
+                    scope.spawn(move || {
+                        sources
+                            .iter()
+                            .map(|source| Analysis::eccentricity_of(maze, *source))
+                            .collect()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("Analysis thread panicked"))
+                .collect()
+        });
+
+        let mut eccentricities = HashMap::with_capacity(cells.len());
+        for result in results {
+            eccentricities.extend(result?);
+        }
+        Ok(Analysis { eccentricities })
+    }
+
+    fn eccentricity_of<T: Direction, M: Maze<T>>(maze: &M, source: Cell) -> Result<(Cell, u32)> {
+        let regions = breadth_first(maze, &[source]);
+        let distances = regions.distances();
+
+        if let Some(cell) = maze
+            .cells()
+            .into_iter()
+            .find(|c| !distances.is_reachable(**c))
+        {
+            return Err(Error::Unreachable(*cell));
+        }
+        Ok((
+            source,
+            distances.all_cells().values().copied().max().unwrap_or(0),
+        ))
+    }
+
+    /// Return the greatest distance from the cell to any other cell
+    pub fn eccentricity(&self, cell: Cell) -> Option<u32> {
+        self.eccentricities.get(&cell).copied()
+    }
+
+    pub fn all_eccentricities(&self) -> &HashMap<Cell, u32> {
+        &self.eccentricities
+    }
+
+    /// Return the smallest eccentricity, or None for an empty maze
+    pub fn radius(&self) -> Option<u32> {
+        self.eccentricities.values().copied().min()
+    }
+
+    /// Return the largest eccentricity, the longest shortest path in the maze
+    pub fn diameter(&self) -> Option<u32> {
+        self.eccentricities.values().copied().max()
+    }
+
+    /// Return the cells whose eccentricity equals the radius
+    pub fn centre(&self) -> Vec<Cell> {
+        match self.radius() {
+            Some(radius) => self
+                .eccentricities
+                .iter()
+                .filter(|(_, e)| **e == radius)
+                .map(|(c, _)| *c)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_analysis() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let analysis = Analysis::new(&grid).expect("Could not analyse maze");
+        let centre = *grid.cell(0, 1).expect("Missing cell 0,1");
+
+        assert_eq!(analysis.all_eccentricities().len(), 9);
+        assert_eq!(analysis.diameter(), Some(6));
+        assert_eq!(analysis.radius(), Some(3));
+        assert_eq!(analysis.centre(), vec![centre]);
+        assert_eq!(analysis.eccentricity(centre), Some(3));
+    }
+
+    #[test]
+    fn check_analysis_disconnected() {
+        let grid = Grid::square(2);
+
+        assert!(matches!(Analysis::new(&grid), Err(Error::Unreachable(_))));
+    }
+
+    #[test]
+    fn check_analysis_loop() {
+        // four cells linked in a ring, a depth first flood would go the long way round
+        let mut grid = Grid::square(2);
+        for (row, column, direction) in [
+            (0, 0, Compass::East),
+            (0, 1, Compass::South),
+            (1, 1, Compass::West),
+            (1, 0, Compass::North),
+        ]
+        .iter()
+        {
+            let cell = *grid.cell(*row, *column).expect("Missing cell");
+            grid.link_cell(&cell, *direction);
+        }
+
+        let analysis = Analysis::new(&grid).expect("Could not analyse maze");
+
+        assert_eq!(analysis.diameter(), Some(2));
+        assert_eq!(analysis.radius(), Some(2));
+    }
+}
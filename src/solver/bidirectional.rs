@@ -0,0 +1,162 @@
+use crate::error::{Error, Result};
+use crate::maze::{Cell, Direction, Maze};
+use std::collections::HashMap;
+
+/// Breadth first search run from both ends at once, meeting in the middle
+pub struct Bidirectional {}
+
+#[allow(dead_code)]
+impl Bidirectional {
+    pub fn new() -> Self {
+        Bidirectional {}
+    }
+
+    /// Return the shortest path between two cells, including both ends
+    ///
+    /// # Arguments
+    /// * `maze` - maze to search
+    /// * `start` - starting row and column
+    /// * `goal` - target row and column
+    pub fn path<T: Direction, M: Maze<T>>(
+        &self,
+        maze: &M,
+        start: (u32, u32),
+        goal: (u32, u32),
+    ) -> Result<Vec<Cell>> {
+        let start = maze.valid_cell(start.0, start.1)?;
+        let goal = maze.valid_cell(goal.0, goal.1)?;
+        let mut forward = Search::new(start);
+        let mut backward = Search::new(goal);
+
+        let meeting = if start == goal {
+            Some(start)
+        } else {
+            loop {
+                if forward.frontier.is_empty() || backward.frontier.is_empty() {
+                    break None;
+                }
+                // always grow the smaller frontier to keep the search balanced
+                let met = if forward.frontier.len() <= backward.frontier.len() {
+                    forward.expand(maze, &backward)
+                } else {
+                    backward.expand(maze, &forward)
+                };
+                if met.is_some() {
+                    break met;
+                }
+            }
+        };
+
+        match meeting {
+            Some(cell) => {
+                let mut path = forward.trace(cell);
+                path.reverse();
+                path.extend(backward.trace(cell).into_iter().skip(1));
+                Ok(path)
+            }
+            None => Err(Error::Unreachable(goal)),
+        }
+    }
+}
+
+struct Search {
+    frontier: Vec<Cell>,
+    parents: HashMap<Cell, Option<Cell>>,
+}
+
+impl Search {
+    fn new(cell: Cell) -> Search {
+        let mut parents = HashMap::new();
+        parents.insert(cell, None);
+
+        Search {
+            frontier: vec![cell],
+            parents,
+        }
+    }
+
+    /// Expand one level of the frontier, returning a cell if it was already seen by the other side
+    fn expand<T: Direction, M: Maze<T>>(&mut self, maze: &M, other: &Search) -> Option<Cell> {
+        let mut next = Vec::new();
+
+        for cell in &self.frontier {
+            for direction in T::all() {
+                if let Some(neighbour) = maze.linked_neighbour(cell, direction) {
+                    if self.parents.contains_key(&neighbour) {
+                        continue;
+                    }
+                    self.parents.insert(neighbour, Some(*cell));
+                    if other.parents.contains_key(&neighbour) {
+                        return Some(neighbour);
+                    }
+                    next.push(neighbour);
+                }
+            }
+        }
+        self.frontier = next;
+        None
+    }
+
+    /// Return the cells from the given cell back to the origin of the search
+    fn trace(&self, cell: Cell) -> Vec<Cell> {
+        let mut path = vec![cell];
+        let mut current = cell;
+
+        while let Some(Some(parent)) = self.parents.get(&current) {
+            path.push(*parent);
+            current = *parent;
+        }
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_path() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let path = Bidirectional::new()
+            .path(&grid, (2, 0), (2, 2))
+            .expect("Missing path");
+        let coords: Vec<(u32, u32)> = path.iter().map(|c| c.coords()).collect();
+
+        assert_eq!(
+            coords,
+            vec![(2, 0), (2, 1), (1, 1), (0, 1), (0, 2), (1, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn check_path_same_cell() {
+        let grid = Grid::square(2);
+
+        let path = Bidirectional::new()
+            .path(&grid, (1, 1), (1, 1))
+            .expect("Missing path");
+
+        assert_eq!(path.len(), 1);
+    }
+
+    #[test]
+    fn check_path_unreachable() {
+        let grid = Grid::square(2);
+        let goal = *grid.cell(1, 1).expect("Missing cell 1,1");
+
+        assert_eq!(
+            Bidirectional::new().path(&grid, (0, 0), (1, 1)),
+            Err(Error::Unreachable(goal))
+        );
+    }
+}
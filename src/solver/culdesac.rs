@@ -0,0 +1,198 @@
+use crate::error::Result;
+use crate::maze::{Cell, Direction, Maze};
+use crate::solver::Filler;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+
+/// Fill every blind alley, any region joined to the rest of the maze by a single passage that
+/// holds neither the start nor the goal, including cul-de-sacs that loop back on themselves
+pub struct CulDeSac {}
+
+struct Search {
+    order: Vec<Cell>,
+    discovered: HashMap<Cell, usize>,
+    low: HashMap<Cell, usize>,
+    /// ranges of `order` beyond a bridge to a blind alley, nested ranges are fine
+    blind: Vec<(usize, usize)>,
+}
+
+/// Cell on the search stack, with how far through its directions the search has got
+struct Frame<T> {
+    cell: Cell,
+    parent: Option<Cell>,
+    directions: Vec<T>,
+    next: usize,
+    kept: bool,
+}
+
+impl Search {
+    fn enter<T: Direction>(&mut self, cell: Cell, parent: Option<Cell>, keep: &[Cell]) -> Frame<T> {
+        let index = self.order.len();
+        self.order.push(cell);
+        self.discovered.insert(cell, index);
+        self.low.insert(cell, index);
+        Frame {
+            cell,
+            parent,
+            directions: T::all(),
+            next: 0,
+            kept: keep.contains(&cell),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl CulDeSac {
+    pub fn new() -> Self {
+        CulDeSac {}
+    }
+
+    /// Depth first search marking bridges, an explicit stack keeps long corridors off the call
+    /// stack
+    fn search<T: Direction, M: Maze<T>>(&self, maze: &M, start: Cell, keep: &[Cell]) -> Search {
+        let mut search = Search {
+            order: Vec::new(),
+            discovered: HashMap::new(),
+            low: HashMap::new(),
+            blind: Vec::new(),
+        };
+        let mut stack: Vec<Frame<T>> = vec![search.enter(start, None, keep)];
+
+        while let Some(frame) = stack.last_mut() {
+            if let Some(direction) = frame.directions.get(frame.next).copied() {
+                frame.next += 1;
+                let cell = frame.cell;
+                if let Some(next) = maze.linked_neighbour(&cell, direction) {
+                    if Some(next) == frame.parent {
+                        continue;
+                    }
+                    if let Some(d) = search.discovered.get(&next).copied() {
+                        search.low.insert(cell, cmp::min(search.low[&cell], d));
+                    } else {
+                        let child = search.enter(next, Some(cell), keep);
+                        stack.push(child);
+                    }
+                }
+                continue;
+            }
+
+            // every direction searched, hand the result back to the parent
+            let child = stack.pop().expect("Missing frame");
+            if let Some(parent) = stack.last_mut() {
+                let index = search.discovered[&parent.cell];
+                let low = search.low[&child.cell];
+                search
+                    .low
+                    .insert(parent.cell, cmp::min(search.low[&parent.cell], low));
+
+                if low > index && !child.kept {
+                    // bridge to a blind alley, fill everything found beyond it
+                    let first = search.discovered[&child.cell];
+                    search.blind.push((first, search.order.len()));
+                }
+                parent.kept |= child.kept;
+            }
+        }
+        search
+    }
+}
+
+impl<T: Direction, M: Maze<T>> Filler<T, M> for CulDeSac {
+    fn fill(&self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<HashSet<Cell>> {
+        let start = maze.valid_cell(start.0, start.1)?;
+        let goal = maze.valid_cell(goal.0, goal.1)?;
+        let search = self.search(maze, start, &[start, goal]);
+
+        // count how many blind ranges cover each cell in one pass, rather than filling nested
+        // alleys again and again
+        let mut depth = vec![0i64; search.order.len() + 1];
+        for (first, end) in search.blind.iter() {
+            depth[*first] += 1;
+            depth[*end] -= 1;
+        }
+        let mut covered = 0;
+
+        // cells never reached from the start cannot be on the solution
+        Ok(search
+            .order
+            .iter()
+            .zip(depth)
+            .filter(|(_, d)| {
+                covered += d;
+                covered == 0
+            })
+            .map(|(c, _)| *c)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::maze::style::RenderStyle;
+    use crate::router::internal::NoOp;
+    use crate::router::sidewinder::SideWinder;
+    use crate::solver::internal::cul_de_sac;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_fill_perfect() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let remaining = CulDeSac::new()
+            .fill(&grid, (2, 0), (2, 2))
+            .expect("Could not fill maze");
+
+        assert_eq!(remaining.len(), 7);
+    }
+
+    #[test]
+    fn check_fill_loop() {
+        let grid = cul_de_sac();
+
+        let remaining = CulDeSac::new()
+            .fill(&grid, (2, 0), (2, 2))
+            .expect("Could not fill maze");
+
+        assert_eq!(remaining.len(), 3);
+        assert!(remaining.contains(grid.cell(2, 1).expect("Missing cell 2,1")));
+    }
+
+    #[test]
+    fn check_fill_long_corridor() {
+        // deep enough to overflow the stack if the search recursed
+        let mut grid = Grid::grid(1, 100_000, Grid::ALLOW_ALL, &mut NoOp {});
+        for column in 0..99_999 {
+            let cell = *grid.cell(0, column).expect("Missing cell");
+            grid.link_cell(&cell, Compass::East);
+        }
+
+        let remaining = CulDeSac::new()
+            .fill(&grid, (0, 0), (0, 50_000))
+            .expect("Could not fill maze");
+
+        assert_eq!(remaining.len(), 50_001);
+    }
+
+    #[test]
+    fn check_apply_fill() {
+        let mut grid = cul_de_sac();
+        let remaining = CulDeSac::new()
+            .fill(&grid, (2, 0), (2, 2))
+            .expect("Could not fill maze");
+
+        grid.apply_fill(&remaining);
+        let image = grid.draw_image(&RenderStyle::default());
+
+        assert_eq!(image.get_pixel(25, 25), &image::Rgb([192u8, 192u8, 192u8])); // filled = silver
+        assert_eq!(image.get_pixel(25, 35), &image::Rgb([255u8, 255u8, 255u8]));
+        // path = white
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::Result;
+use crate::maze::{Cell, Direction, Maze};
+use crate::solver::Filler;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Repeatedly fill any cell with at most one open link until only the solution remains
+pub struct DeadEnd {}
+
+#[allow(dead_code)]
+impl DeadEnd {
+    pub fn new() -> Self {
+        DeadEnd {}
+    }
+}
+
+impl<T: Direction, M: Maze<T>> Filler<T, M> for DeadEnd {
+    fn fill(&self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<HashSet<Cell>> {
+        let start = maze.valid_cell(start.0, start.1)?;
+        let goal = maze.valid_cell(goal.0, goal.1)?;
+        let mut degrees: HashMap<Cell, usize> = maze
+            .cells()
+            .into_iter()
+            .map(|c| (*c, maze.links(c).len()))
+            .collect();
+        let mut queue: VecDeque<Cell> = maze
+            .cells()
+            .into_iter()
+            .filter(|c| **c != start && **c != goal && degrees[*c] <= 1)
+            .copied()
+            .collect();
+        let mut filled = HashSet::new();
+
+        while let Some(cell) = queue.pop_front() {
+            if !filled.insert(cell) {
+                continue;
+            }
+            for direction in maze.links(&cell) {
+                if let Some(next) = maze.linked_neighbour(&cell, *direction) {
+                    if filled.contains(&next) {
+                        continue;
+                    }
+                    let degree = degrees.get_mut(&next).expect("Missing degree");
+                    *degree -= 1;
+                    if *degree == 1 && next != start && next != goal {
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        Ok(maze
+            .cells()
+            .into_iter()
+            .filter(|c| !filled.contains(*c))
+            .copied()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use crate::solver::internal::cul_de_sac;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_fill_perfect() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let remaining = DeadEnd::new()
+            .fill(&grid, (2, 0), (2, 2))
+            .expect("Could not fill maze");
+
+        assert_eq!(remaining.len(), 7);
+        assert!(!remaining.contains(grid.cell(0, 0).expect("Missing cell 0,0")));
+        assert!(!remaining.contains(grid.cell(1, 0).expect("Missing cell 1,0")));
+    }
+
+    #[test]
+    fn check_fill_loop() {
+        let grid = cul_de_sac();
+
+        let remaining = DeadEnd::new()
+            .fill(&grid, (2, 0), (2, 2))
+            .expect("Could not fill maze");
+
+        // the loop cannot be filled from its dead ends
+        assert_eq!(remaining.len(), 7);
+    }
+}
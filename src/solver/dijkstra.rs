@@ -1,7 +1,9 @@
+use crate::error::Result;
 use crate::maze::{Cell, Direction, Maze};
-use crate::solver::{Distances, Solver};
-use std::collections::HashMap;
+use crate::solver::{breadth_first, Distances, Regions, Solver};
 
+/// Flood the maze outwards from the start, one distance at a time, so every distance is the
+/// shortest path even in mazes with loops
 pub struct Dijkstra {}
 
 #[allow(dead_code)]
@@ -10,44 +12,45 @@ impl Dijkstra {
         Dijkstra {}
     }
 
-    pub fn solve<T: Direction, M: Maze<T>>(grid: &M, start: (u32, u32)) -> Distances {
+    pub fn solve<T: Direction, M: Maze<T>>(grid: &M, start: (u32, u32)) -> Result<Distances> {
         Dijkstra::new().solve(grid, start)
     }
 
-    fn frontier<T: Direction, M: Maze<T>>(
-        &self,
-        map: &mut HashMap<Cell, u32>,
-        maze: &M,
-        cell: Cell,
-        depth: u32,
-    ) {
-        let neighbours = maze.neighbours(&cell);
-        map.insert(cell, depth);
-
-        for direction in maze.links(&cell) {
-            if let Some(c) = neighbours.get(direction) {
-                if !map.contains_key(c) {
-                    self.frontier(map, maze, *c, depth + 1);
-                }
-            }
-        }
+    /// Solve from several start cells, each cell taking the distance to its nearest source
+    ///
+    /// All the sources are flooded together in a single pass rather than solving once per
+    /// source. Ties are won by the source that appears first in the list.
+    ///
+    /// # Arguments
+    /// * `grid` - maze to solve
+    /// * `starts` - row and column of each source cell
+    pub fn solve_many<T: Direction, M: Maze<T>>(
+        grid: &M,
+        starts: &[(u32, u32)],
+    ) -> Result<Regions> {
+        let sources = starts
+            .iter()
+            .map(|(row, column)| grid.valid_cell(*row, *column))
+            .collect::<Result<Vec<Cell>>>()?;
+
+        Ok(breadth_first(grid, &sources))
     }
 }
 
 impl<T: Direction, M: Maze<T>> Solver<T, M> for Dijkstra {
-    fn solve(&self, maze: &M, start: (u32, u32)) -> Distances {
-        let cell = maze.cell(start.0, start.1).expect("Invalid starting cell");
-        let mut map = HashMap::new();
-        self.frontier(&mut map, maze, *cell, 0);
+    fn solve(&self, maze: &M, start: (u32, u32)) -> Result<Distances> {
+        let cell = maze.valid_cell(start.0, start.1)?;
 
-        Distances::new(map)
+        Ok(breadth_first(maze, &[cell]).into_distances())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
     use crate::maze::grid::{Compass, Grid};
+    use crate::router::internal::NoOp;
     use crate::router::sidewinder::SideWinder;
     use rand::rngs::mock::StepRng;
 
@@ -62,12 +65,12 @@ mod tests {
         );
 
         let solver = Dijkstra::new();
-        let distances = solver.solve(&grid, (2, 0));
+        let distances = solver.solve(&grid, (2, 0)).expect("Could not solve maze");
 
-        assert_eq!(distances.start().coords(), (2, 0));
+        assert_eq!(distances.start().expect("Missing start").coords(), (2, 0));
         assert_eq!(
             distances.distance(*grid.cell(2, 2).expect("Missing cell 2,2")),
-            6
+            Ok(6)
         );
         assert_eq!(distances.cells(0).len(), 1);
         assert_eq!(distances.cells(1).len(), 1);
@@ -77,6 +80,8 @@ mod tests {
         assert_eq!(distances.cells(5).len(), 2);
         assert_eq!(distances.cells(6).len(), 1);
         assert_eq!(distances.cells(7).len(), 0);
+        assert_eq!(distances.order().len(), 9);
+        assert_eq!(distances.order()[0].coords(), (2, 0));
     }
 
     #[test]
@@ -88,7 +93,123 @@ mod tests {
             Grid::ALLOW_ALL,
             &mut SideWinder::<Compass>::new_for_compass(&mut rng),
         );
-        let distances = Dijkstra::solve(&grid, (0, 0));
-        assert_eq!(distances.start().coords(), (0, 0));
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+        assert_eq!(distances.start().expect("Missing start").coords(), (0, 0));
+    }
+
+    #[test]
+    fn check_solve_invalid_start() {
+        let grid = Grid::grid(2, 2, |r, c| r != 0 || c != 0, &mut NoOp {});
+
+        assert_eq!(
+            Dijkstra::solve(&grid, (0, 0)).unwrap_err(),
+            Error::InvalidCell(0, 0)
+        );
+        assert_eq!(
+            Dijkstra::solve(&grid, (2, 0)).unwrap_err(),
+            Error::InvalidCell(2, 0)
+        );
+    }
+
+    #[test]
+    fn check_solve_unreachable() {
+        let grid = Grid::square(2);
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+        let cell = *grid.cell(1, 1).expect("Missing cell 1,1");
+
+        assert!(!distances.is_reachable(cell));
+        assert_eq!(distances.distance(cell), Err(Error::Unreachable(cell)));
+    }
+
+    #[test]
+    fn check_solve_loop() {
+        // four cells linked in a ring, the far corner is two steps either way round
+        let mut grid = Grid::square(2);
+        for (row, column, direction) in [
+            (0, 0, Compass::East),
+            (0, 1, Compass::South),
+            (1, 1, Compass::West),
+            (1, 0, Compass::North),
+        ]
+        .iter()
+        {
+            let cell = *grid.cell(*row, *column).expect("Missing cell");
+            grid.link_cell(&cell, *direction);
+        }
+
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+
+        assert_eq!(distances.distance(*grid.cell(1, 0).unwrap()), Ok(1));
+        assert_eq!(distances.distance(*grid.cell(1, 1).unwrap()), Ok(2));
+    }
+
+    #[test]
+    fn check_solve_long_corridor() {
+        // deep enough to overflow the stack if the search recursed
+        let mut grid = Grid::grid(1, 100_000, Grid::ALLOW_ALL, &mut NoOp {});
+        for column in 0..99_999 {
+            let cell = *grid.cell(0, column).expect("Missing cell");
+            grid.link_cell(&cell, Compass::East);
+        }
+
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+
+        assert_eq!(
+            distances.distance(*grid.cell(0, 99_999).expect("Missing cell")),
+            Ok(99_999)
+        );
+    }
+
+    #[test]
+    fn check_solve_many() {
+        let mut grid = Grid::square(4);
+        for cell in grid.cells().into_iter().copied().collect::<Vec<Cell>>() {
+            grid.link_cell(&cell, Compass::East);
+            grid.link_cell(&cell, Compass::South);
+        }
+        let regions = Dijkstra::solve_many(&grid, &[(0, 0), (3, 3)]).expect("Could not solve maze");
+        let far = *grid.cell(3, 2).expect("Missing cell 3,2");
+        let tie = *grid.cell(0, 3).expect("Missing cell 0,3");
+
+        assert_eq!(regions.sources().len(), 2);
+        assert_eq!(regions.distances().cells(0).len(), 2);
+        assert_eq!(regions.distances().distance(far), Ok(1));
+        assert_eq!(regions.owner(far), Some(1));
+        assert_eq!(regions.owner(tie), Some(0));
+        assert_eq!(regions.territory(0).len() + regions.territory(1).len(), 16);
+    }
+
+    #[test]
+    fn check_solve_many_invalid() {
+        let grid = Grid::square(2);
+
+        assert_eq!(
+            Dijkstra::solve_many(&grid, &[(0, 0), (2, 0)]).unwrap_err(),
+            Error::InvalidCell(2, 0)
+        );
+    }
+
+    #[test]
+    fn check_path_to() {
+        let mut grid = Grid::square(2);
+        let cell_00 = *grid.cell(0, 0).expect("Missing cell 0,0");
+        let cell_11 = *grid.cell(1, 1).expect("Missing cell 1,1");
+        grid.link_cell(&cell_00, Compass::East);
+        grid.link_cell(&cell_11, Compass::North);
+        let distances = Dijkstra::solve(&grid, (0, 0)).expect("Could not solve maze");
+
+        let path: Vec<(u32, u32)> = distances
+            .path_to(&grid, cell_11)
+            .expect("Could not find path")
+            .iter()
+            .map(|c| c.coords())
+            .collect();
+
+        assert_eq!(path, vec![(0, 0), (0, 1), (1, 1)]);
+        let cell_10 = *grid.cell(1, 0).expect("Missing cell 1,0");
+        assert_eq!(
+            distances.path_to(&grid, cell_10),
+            Err(Error::Unreachable(cell_10))
+        );
     }
 }
@@ -1,27 +1,185 @@
+pub mod analysis;
+pub mod bidirectional;
+pub mod culdesac;
+pub mod deadend;
 pub mod dijkstra;
+pub mod randommouse;
+pub mod tremaux;
+pub mod wallfollower;
 
+use crate::error::{Error, Result};
 use crate::maze::{Cell, Direction, Maze};
 
 #[allow(unused_imports)]
 use crate::util::math;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 pub trait Solver<T: Direction, M: Maze<T>> {
-    fn solve(&self, grid: &M, start: (u32, u32)) -> Distances;
+    fn solve(&self, grid: &M, start: (u32, u32)) -> Result<Distances>;
+}
+
+/// Distances to the nearest of several sources, along with the source that owns each cell
+#[derive(Debug)]
+pub struct Regions {
+    distances: Distances,
+    owners: HashMap<Cell, usize>,
+    sources: Vec<Cell>,
+}
+
+#[allow(dead_code)]
+impl Regions {
+    pub fn new(distances: Distances, owners: HashMap<Cell, usize>, sources: Vec<Cell>) -> Regions {
+        Regions {
+            distances,
+            owners,
+            sources,
+        }
+    }
+
+    pub fn distances(&self) -> &Distances {
+        &self.distances
+    }
+
+    pub fn into_distances(self) -> Distances {
+        self.distances
+    }
+
+    pub fn sources(&self) -> &[Cell] {
+        &self.sources
+    }
+
+    /// Return the index of the source nearest to the cell, or None if it is unreachable
+    pub fn owner(&self, cell: Cell) -> Option<usize> {
+        self.owners.get(&cell).copied()
+    }
+
+    pub fn all_owners(&self) -> &HashMap<Cell, usize> {
+        &self.owners
+    }
+
+    /// Return all the cells owned by the source at the index
+    pub fn territory(&self, owner: usize) -> Vec<Cell> {
+        self.owners
+            .iter()
+            .filter(|(_, o)| **o == owner)
+            .map(|(c, _)| *c)
+            .collect()
+    }
+}
+
+/// Breadth first search from every source at once, each cell taking the distance to its
+/// nearest source, ties are won by the source that appears first in the list
+///
+/// Unlike a depth first flood the distances are shortest paths even when the maze has loops.
+pub(crate) fn breadth_first<T: Direction, M: Maze<T>>(maze: &M, sources: &[Cell]) -> Regions {
+    let mut cells = HashMap::new();
+    let mut owners = HashMap::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for (index, source) in sources.iter().enumerate() {
+        if !cells.contains_key(source) {
+            cells.insert(*source, 0);
+            owners.insert(*source, index);
+            queue.push_back(*source);
+        }
+    }
+    // each level of the queue stays in source order, so the first source wins any tie
+    while let Some(cell) = queue.pop_front() {
+        order.push(cell);
+        for direction in T::all() {
+            if let Some(next) = maze.linked_neighbour(&cell, direction) {
+                if !cells.contains_key(&next) {
+                    cells.insert(next, cells[&cell] + 1);
+                    owners.insert(next, owners[&cell]);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    Regions::new(
+        Distances::with_order(cells, order),
+        owners,
+        sources.to_vec(),
+    )
+}
+
+/// Solver that simulates an agent walking the maze with only local knowledge
+#[allow(dead_code)]
+pub trait Agent<T: Direction, M: Maze<T>> {
+    /// Walk from the start cell until the goal is reached or the agent gives up
+    ///
+    /// # Arguments
+    /// * `maze` - maze to walk
+    /// * `start` - starting row and column
+    /// * `goal` - target row and column
+    fn walk(&mut self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<Trace>;
+}
+
+/// Solver that works on the whole maze, filling in cells that cannot be on the solution
+#[allow(dead_code)]
+pub trait Filler<T: Direction, M: Maze<T>> {
+    /// Return the cells that remain unfilled, always including the start and goal
+    ///
+    /// # Arguments
+    /// * `maze` - maze to fill
+    /// * `start` - starting row and column
+    /// * `goal` - target row and column
+    fn fill(&self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<HashSet<Cell>>;
+}
+
+/// Cells visited by an agent, in order, and whether it reached the goal
+#[derive(Debug)]
+pub struct Trace {
+    steps: Vec<Cell>,
+    solved: bool,
+}
+
+#[allow(dead_code)]
+impl Trace {
+    pub fn new(steps: Vec<Cell>, solved: bool) -> Trace {
+        Trace { steps, solved }
+    }
+
+    pub fn steps(&self) -> &[Cell] {
+        &self.steps
+    }
+
+    pub fn solved(&self) -> bool {
+        self.solved
+    }
+
+    /// Return the final cell reached by the agent
+    pub fn end(&self) -> Option<&Cell> {
+        self.steps.last()
+    }
 }
 
 #[derive(Debug)]
 pub struct Distances {
     cells: HashMap<Cell, u32>,
     distances: HashMap<u32, Vec<Cell>>,
+    order: Vec<Cell>,
 }
 
 #[allow(dead_code)]
 impl Distances {
+    /// Build distances without a known expansion order, cells are ordered by distance
     pub fn new(cells: HashMap<Cell, u32>) -> Distances {
+        let mut order: Vec<Cell> = cells.keys().copied().collect();
+        order.sort_by_key(|c| (cells[c], c.row(), c.column()));
+        Distances::with_order(cells, order)
+    }
+
+    /// Build distances keeping the order in which the solver expanded each cell
+    pub fn with_order(cells: HashMap<Cell, u32>, order: Vec<Cell>) -> Distances {
         let distances = Distances::build_distances(&cells);
-        Distances { cells, distances }
+        Distances {
+            cells,
+            distances,
+            order,
+        }
     }
 
     fn build_distances(cells: &HashMap<Cell, u32>) -> HashMap<u32, Vec<Cell>> {
@@ -36,46 +194,81 @@ impl Distances {
         distances
     }
 
-    pub fn start(&self) -> Cell {
-        *self
-            .distances
+    pub fn start(&self) -> Result<Cell> {
+        self.distances
             .get(&0)
-            .expect("No cells at distance zero")
-            .get(0)
-            .expect("Empty list of cells at distance zero")
+            .and_then(|cells| cells.first())
+            .copied()
+            .ok_or(Error::NoStart)
     }
 
     pub fn cells(&self, distance: u32) -> &[Cell] {
         if let Some(cells) = self.distances.get(&distance) {
-            &cells
+            cells
         } else {
             <&[Cell]>::default()
         }
     }
 
-    pub fn distance(&self, cell: Cell) -> u32 {
-        *self
-            .cells
+    /// Return the distance to the cell, or an error if it cannot be reached from the start
+    pub fn distance(&self, cell: Cell) -> Result<u32> {
+        self.cells
             .get(&cell)
-            .unwrap_or_else(|| panic!("Missing distance for {:?}", cell))
+            .copied()
+            .ok_or(Error::Unreachable(cell))
+    }
+
+    pub fn is_reachable(&self, cell: Cell) -> bool {
+        self.cells.contains_key(&cell)
     }
 
     pub fn all_cells(&self) -> &HashMap<Cell, u32> {
         &self.cells
     }
+
+    /// Return the cells in the order they were expanded by the solver
+    pub fn order(&self) -> &[Cell] {
+        &self.order
+    }
+
+    /// Return the shortest path from the start to the goal by stepping back through linked
+    /// neighbours one distance closer each time
+    pub fn path_to<T: Direction, M: Maze<T>>(&self, maze: &M, goal: Cell) -> Result<Vec<Cell>> {
+        let mut cell = goal;
+        let mut distance = self.distance(goal)?;
+        let mut path = vec![goal];
+
+        while distance > 0 {
+            cell = maze
+                .links(&cell)
+                .iter()
+                .filter_map(|d| maze.neighbours(&cell).get(d))
+                .find(|c| self.cells.get(c) == Some(&(distance - 1)))
+                .copied()
+                .ok_or(Error::Unreachable(cell))?;
+            distance -= 1;
+            path.push(cell);
+        }
+        path.reverse();
+        Ok(path)
+    }
 }
 
-mod internal {
+pub(crate) mod internal {
     use super::{Distances, Solver};
+    use crate::error::Result;
+    #[cfg(test)]
+    use crate::maze::grid::{Compass, Grid};
     use crate::maze::{Direction, Maze};
     use crate::util::math;
 
     use std::collections::HashMap;
 
+    #[allow(dead_code)]
     pub struct SimpleSolver {}
 
     impl<T: Direction, M: Maze<T>> Solver<T, M> for SimpleSolver {
-        fn solve(&self, grid: &M, start: (u32, u32)) -> Distances {
+        fn solve(&self, grid: &M, start: (u32, u32)) -> Result<Distances> {
             let mut map = HashMap::new();
             let (row, column) = start;
 
@@ -85,9 +278,31 @@ mod internal {
                     math::diff(row, cell.row()) + math::diff(column, cell.column()),
                 );
             }
-            Distances::new(map)
+            Ok(Distances::new(map))
         }
     }
+
+    /// Return a 3x3 grid with a straight path along the bottom and a loop hanging off the
+    /// middle, for the filling solvers
+    #[cfg(test)]
+    pub(crate) fn cul_de_sac() -> Grid {
+        let mut grid = Grid::square(3);
+        for (row, column, direction) in [
+            (2, 0, Compass::East),
+            (2, 1, Compass::East),
+            (2, 1, Compass::North),
+            (1, 1, Compass::West),
+            (1, 0, Compass::North),
+            (0, 0, Compass::East),
+            (0, 1, Compass::South),
+        ]
+        .iter()
+        {
+            let cell = *grid.cell(*row, *column).expect("Missing cell");
+            grid.link_cell(&cell, *direction);
+        }
+        grid
+    }
 }
 
 #[cfg(test)]
@@ -100,26 +315,32 @@ mod tests {
     #[test]
     fn check_distances_start() {
         let grid = Grid::square(4);
-        let distances = SimpleSolver {}.solve(&grid, (0, 0));
+        let distances = SimpleSolver {}
+            .solve(&grid, (0, 0))
+            .expect("Could not solve maze");
 
-        assert_eq!(distances.start().coords(), (0, 0));
+        assert_eq!(distances.start().expect("Missing start").coords(), (0, 0));
     }
 
     #[test]
     fn check_distances_cell() {
         let grid = Grid::square(4);
-        let distances = SimpleSolver {}.solve(&grid, (0, 0));
+        let distances = SimpleSolver {}
+            .solve(&grid, (0, 0))
+            .expect("Could not solve maze");
 
         assert_eq!(
             distances.distance(*grid.cell(1, 1).expect("Missing cell 1,1")),
-            2
+            Ok(2)
         );
     }
 
     #[test]
     fn check_distances_cells() {
         let grid = Grid::square(4);
-        let distances = SimpleSolver {}.solve(&grid, (0, 0));
+        let distances = SimpleSolver {}
+            .solve(&grid, (0, 0))
+            .expect("Could not solve maze");
 
         assert_eq!(distances.cells(1).len(), 2);
         assert_eq!(distances.cells(4).len(), 3);
@@ -128,11 +349,24 @@ mod tests {
     #[test]
     fn check_distances_all_cells() {
         let grid = Grid::square(4);
-        let distances = SimpleSolver {}.solve(&grid, (0, 0));
+        let distances = SimpleSolver {}
+            .solve(&grid, (0, 0))
+            .expect("Could not solve maze");
 
         assert_eq!(distances.all_cells().len(), 16);
     }
 
+    #[test]
+    fn check_distances_order() {
+        let grid = Grid::square(2);
+        let distances = SimpleSolver {}
+            .solve(&grid, (0, 0))
+            .expect("Could not solve maze");
+        let order: Vec<(u32, u32)> = distances.order().iter().map(|c| c.coords()).collect();
+
+        assert_eq!(order, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+    }
+
     #[test]
     fn check_build_distances() {
         let grid = Grid::square(2);
@@ -143,27 +377,26 @@ mod tests {
         let distances = Distances::build_distances(&map);
 
         assert_eq!(distances.get(&1).unwrap().len(), 1);
-        assert_eq!(distances.get(&1).unwrap().get(0).unwrap().coords(), (0, 0));
+        assert_eq!(distances.get(&1).unwrap().first().unwrap().coords(), (0, 0));
         assert_eq!(distances.get(&2).unwrap().len(), 1);
-        assert_eq!(distances.get(&2).unwrap().get(0).unwrap().coords(), (1, 1));
+        assert_eq!(distances.get(&2).unwrap().first().unwrap().coords(), (1, 1));
     }
 
     #[test]
-    #[should_panic]
     fn check_invalid_distance_zero() {
         let grid = Grid::square(2);
         let mut map = HashMap::new();
         map.insert(*grid.cell(0, 0).unwrap(), 1);
 
         let distances = Distances::new(map);
-        distances.start();
+        assert_eq!(distances.start(), Err(Error::NoStart));
     }
 
     #[test]
     fn check_string_distances() {
         let mut grid = Grid::square(2);
         let solver = SimpleSolver {};
-        let distances = solver.solve(&grid, (0, 0));
+        let distances = solver.solve(&grid, (0, 0)).expect("Could not solve maze");
 
         grid.apply_distances(distances);
 
@@ -0,0 +1,101 @@
+use crate::error::Result;
+use crate::maze::{Direction, Maze};
+use crate::solver::{Agent, Trace};
+use rand::{Rng, RngCore};
+
+#[allow(dead_code)]
+pub struct RandomMouse<'a> {
+    rng: &'a mut dyn RngCore,
+    limit: usize,
+}
+
+#[allow(dead_code)]
+impl<'a> RandomMouse<'a> {
+    /// Build a new random mouse
+    ///
+    /// # Arguments
+    /// * `rng` - random number generator used to pick directions
+    /// * `limit` - maximum number of moves before giving up
+    pub fn new(rng: &'a mut dyn RngCore, limit: usize) -> Self {
+        RandomMouse { rng, limit }
+    }
+}
+
+impl<'a, T: Direction, M: Maze<T>> Agent<T, M> for RandomMouse<'a> {
+    fn walk(&mut self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<Trace> {
+        let mut cell = maze.valid_cell(start.0, start.1)?;
+        let goal = maze.valid_cell(goal.0, goal.1)?;
+        let mut back: Option<T> = None;
+        let mut steps = vec![cell];
+
+        while cell != goal {
+            if steps.len() > self.limit {
+                return Ok(Trace::new(steps, false));
+            }
+            let exits: Vec<T> = T::all()
+                .into_iter()
+                .filter(|d| maze.links(&cell).contains(d))
+                .collect();
+            // only turn back when at a dead end
+            let choices: Vec<T> = match exits.iter().filter(|d| Some(**d) != back).count() {
+                0 => exits,
+                _ => exits.into_iter().filter(|d| Some(*d) != back).collect(),
+            };
+            let direction = match choices.len() {
+                0 => return Ok(Trace::new(steps, false)),
+                1 => choices[0],
+                range => choices[self.rng.gen::<usize>() % range],
+            };
+
+            cell = maze
+                .linked_neighbour(&cell, direction)
+                .expect("Missing linked cell");
+            back = Some(direction.reverse());
+            steps.push(cell);
+        }
+        Ok(Trace::new(steps, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_walk() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let mut rng = StepRng::new(0, 1);
+        let trace = RandomMouse::new(&mut rng, 100)
+            .walk(&grid, (2, 0), (2, 2))
+            .expect("Could not walk maze");
+
+        assert!(trace.solved());
+        assert_eq!(trace.steps()[0].coords(), (2, 0));
+        assert_eq!(trace.end().expect("Empty trace").coords(), (2, 2));
+    }
+
+    #[test]
+    fn check_walk_limit() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+
+        let mut rng = StepRng::new(0, 1);
+        let trace = RandomMouse::new(&mut rng, 10)
+            .walk(&grid, (0, 0), (1, 1))
+            .expect("Could not walk maze");
+
+        assert!(!trace.solved());
+        assert_eq!(trace.steps().len(), 11);
+    }
+}
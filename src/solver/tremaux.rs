@@ -0,0 +1,126 @@
+use crate::error::Result;
+use crate::maze::{Cell, Direction, Maze};
+use crate::solver::{Agent, Trace};
+use std::collections::{HashMap, HashSet};
+
+pub struct Tremaux {}
+
+#[allow(dead_code)]
+impl Tremaux {
+    pub fn new() -> Self {
+        Tremaux {}
+    }
+
+    fn marks<T: Direction>(marks: &HashMap<(Cell, T), u32>, cell: Cell, direction: T) -> u32 {
+        *marks.get(&(cell, direction)).unwrap_or(&0)
+    }
+
+    /// Choose the next passage to take, or None if every passage has been marked twice
+    ///
+    /// # Arguments
+    /// * `marks` - number of times each passage has been walked
+    /// * `exits` - linked directions out of the cell
+    /// * `back` - direction of the passage we arrived through
+    /// * `revisit` - whether the cell had been visited before arriving
+    fn choose<T: Direction>(
+        marks: &HashMap<(Cell, T), u32>,
+        cell: Cell,
+        exits: &[T],
+        back: Option<T>,
+        revisit: bool,
+    ) -> Option<T> {
+        if let Some(b) = back {
+            // arrived at a known junction along a fresh passage, so turn around
+            if revisit && Tremaux::marks(marks, cell, b) == 1 {
+                return Some(b);
+            }
+        }
+        exits
+            .iter()
+            .filter(|d| Some(**d) != back && Tremaux::marks(marks, cell, **d) < 2)
+            .min_by_key(|d| Tremaux::marks(marks, cell, **d))
+            .copied()
+            .or_else(|| back.filter(|b| Tremaux::marks(marks, cell, *b) < 2))
+    }
+}
+
+impl<T: Direction, M: Maze<T>> Agent<T, M> for Tremaux {
+    fn walk(&mut self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<Trace> {
+        let mut cell = maze.valid_cell(start.0, start.1)?;
+        let goal = maze.valid_cell(goal.0, goal.1)?;
+        let mut marks = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut back: Option<T> = None;
+        let mut steps = vec![cell];
+
+        while cell != goal {
+            let revisit = !visited.insert(cell);
+            let exits: Vec<T> = T::all()
+                .into_iter()
+                .filter(|d| maze.links(&cell).contains(d))
+                .collect();
+
+            match Tremaux::choose(&marks, cell, &exits, back, revisit) {
+                Some(direction) => {
+                    let next = maze
+                        .linked_neighbour(&cell, direction)
+                        .expect("Missing linked cell");
+
+                    // mark both ends of the passage
+                    *marks.entry((cell, direction)).or_insert(0) += 1;
+                    *marks.entry((next, direction.reverse())).or_insert(0) += 1;
+
+                    back = Some(direction.reverse());
+                    cell = next;
+                    steps.push(cell);
+                }
+                None => return Ok(Trace::new(steps, false)),
+            }
+        }
+        Ok(Trace::new(steps, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_walk() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        let trace = Tremaux::new()
+            .walk(&grid, (2, 0), (2, 2))
+            .expect("Could not walk maze");
+
+        assert!(trace.solved());
+        assert_eq!(trace.steps()[0].coords(), (2, 0));
+        assert_eq!(trace.end().expect("Empty trace").coords(), (2, 2));
+    }
+
+    #[test]
+    fn check_walk_unreachable() {
+        let mut grid = Grid::square(2);
+        let cell = *grid.cell(0, 0).expect("Missing cell 0,0");
+        grid.link_cell(&cell, Compass::East);
+        grid.link_cell(&cell, Compass::South);
+
+        let trace = Tremaux::new()
+            .walk(&grid, (0, 0), (1, 1))
+            .expect("Could not walk maze");
+
+        assert!(!trace.solved());
+        // every passage is walked in both directions before giving up
+        assert_eq!(trace.steps().len(), 5);
+        assert_eq!(trace.end().expect("Empty trace").coords(), (0, 0));
+    }
+}
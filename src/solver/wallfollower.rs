@@ -0,0 +1,145 @@
+use crate::error::Result;
+use crate::maze::{Direction, Maze};
+use crate::solver::{Agent, Trace};
+use std::collections::HashSet;
+
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+pub struct WallFollower {
+    hand: Hand,
+}
+
+#[allow(dead_code)]
+impl WallFollower {
+    pub fn new(hand: Hand) -> Self {
+        WallFollower { hand }
+    }
+
+    /// Return the directions to try, in order, keeping a hand on the wall
+    fn turns<T: Direction>(&self, heading: T) -> Vec<T> {
+        let count = T::all().len();
+        let mut turns = Vec::with_capacity(count);
+        let mut direction = match self.hand {
+            Hand::Left => heading.anticlockwise(),
+            Hand::Right => heading.clockwise(),
+        };
+
+        for _ in 0..count {
+            turns.push(direction);
+            direction = match self.hand {
+                Hand::Left => direction.clockwise(),
+                Hand::Right => direction.anticlockwise(),
+            };
+        }
+        turns
+    }
+}
+
+impl<T: Direction, M: Maze<T>> Agent<T, M> for WallFollower {
+    fn walk(&mut self, maze: &M, start: (u32, u32), goal: (u32, u32)) -> Result<Trace> {
+        let mut cell = maze.valid_cell(start.0, start.1)?;
+        let goal = maze.valid_cell(goal.0, goal.1)?;
+        let mut heading = T::all()[0];
+        let mut seen = HashSet::new();
+        let mut steps = vec![cell];
+
+        while cell != goal {
+            // arriving at the same cell with the same heading means we are going round in circles
+            if !seen.insert((cell, heading)) {
+                return Ok(Trace::new(steps, false));
+            }
+            match self
+                .turns(heading)
+                .into_iter()
+                .find_map(|d| maze.linked_neighbour(&cell, d).map(|c| (d, c)))
+            {
+                Some((direction, next)) => {
+                    heading = direction;
+                    cell = next;
+                    steps.push(cell);
+                }
+                None => return Ok(Trace::new(steps, false)),
+            }
+        }
+        Ok(Trace::new(steps, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::grid::{Compass, Grid};
+    use crate::router::sidewinder::SideWinder;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn check_turns() {
+        assert_eq!(
+            WallFollower::new(Hand::Right).turns(Compass::North),
+            vec![Compass::East, Compass::North, Compass::West, Compass::South]
+        );
+        assert_eq!(
+            WallFollower::new(Hand::Left).turns(Compass::North),
+            vec![Compass::West, Compass::North, Compass::East, Compass::South]
+        );
+    }
+
+    #[test]
+    fn check_walk_perfect() {
+        let mut rng = StepRng::new(1, 1);
+        let grid = Grid::grid(
+            3,
+            3,
+            Grid::ALLOW_ALL,
+            &mut SideWinder::<Compass>::new_for_compass(&mut rng),
+        );
+
+        for hand in [Hand::Left, Hand::Right].iter() {
+            let trace = WallFollower::new(*hand)
+                .walk(&grid, (2, 0), (2, 2))
+                .expect("Could not walk maze");
+
+            assert!(trace.solved());
+            assert_eq!(trace.steps()[0].coords(), (2, 0));
+            assert_eq!(trace.end().expect("Empty trace").coords(), (2, 2));
+        }
+    }
+
+    #[test]
+    fn check_walk_braided() {
+        // ring of cells around a centre island that is only reachable from the north
+        let mut grid = Grid::square(3);
+        for (row, column, direction) in [
+            (0, 0, Compass::East),
+            (0, 1, Compass::East),
+            (0, 2, Compass::South),
+            (1, 2, Compass::South),
+            (2, 2, Compass::West),
+            (2, 1, Compass::West),
+            (2, 0, Compass::North),
+            (1, 0, Compass::North),
+            (1, 1, Compass::North),
+        ]
+        .iter()
+        {
+            let cell = *grid.cell(*row, *column).expect("Missing cell");
+            grid.link_cell(&cell, *direction);
+        }
+
+        let left = WallFollower::new(Hand::Left)
+            .walk(&grid, (1, 0), (1, 1))
+            .expect("Could not walk maze");
+        let right = WallFollower::new(Hand::Right)
+            .walk(&grid, (1, 0), (1, 1))
+            .expect("Could not walk maze");
+
+        assert!(!left.solved());
+        assert!(right.solved());
+        assert_eq!(right.steps().len(), 4);
+    }
+}
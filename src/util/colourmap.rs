@@ -0,0 +1,257 @@
+use crate::error::{Error, Result};
+use crate::util::image::gradient_colour;
+
+use image::Rgb;
+
+/// How colours are blended between two stops
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interpolation {
+    Rgb,
+    /// blend hue, saturation and lightness, the hue moves linearly without wrapping round
+    Hsl,
+}
+
+/// Multi-stop colour gradient used to colour distances
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColourMap {
+    stops: Vec<(f32, Rgb<u8>)>,
+    interpolation: Interpolation,
+    bands: Option<u32>,
+}
+
+#[allow(dead_code)]
+impl ColourMap {
+    /// Build a colour map from custom stops
+    ///
+    /// # Arguments
+    /// * `stops` - position from 0 to 1 and colour of each stop, in any order, at least one
+    pub fn new(stops: Vec<(f32, Rgb<u8>)>) -> Result<Self> {
+        if stops.is_empty() {
+            return Err(Error::EmptyColourMap);
+        }
+        Ok(ColourMap::sorted(stops))
+    }
+
+    fn sorted(mut stops: Vec<(f32, Rgb<u8>)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        ColourMap {
+            stops,
+            interpolation: Interpolation::Rgb,
+            bands: None,
+        }
+    }
+
+    pub fn linear(start: Rgb<u8>, end: Rgb<u8>) -> Self {
+        ColourMap::sorted(vec![(0.0, start), (1.0, end)])
+    }
+
+    pub fn viridis() -> Self {
+        ColourMap::evenly(&[
+            Rgb([68u8, 1u8, 84u8]),
+            Rgb([59u8, 82u8, 139u8]),
+            Rgb([33u8, 145u8, 140u8]),
+            Rgb([94u8, 201u8, 98u8]),
+            Rgb([253u8, 231u8, 37u8]),
+        ])
+    }
+
+    pub fn magma() -> Self {
+        ColourMap::evenly(&[
+            Rgb([0u8, 0u8, 4u8]),
+            Rgb([59u8, 15u8, 112u8]),
+            Rgb([140u8, 41u8, 129u8]),
+            Rgb([222u8, 73u8, 104u8]),
+            Rgb([254u8, 159u8, 109u8]),
+            Rgb([252u8, 253u8, 191u8]),
+        ])
+    }
+
+    /// Hue sweep from red through to magenta
+    pub fn rainbow() -> Self {
+        ColourMap::linear(Rgb([255u8, 0u8, 0u8]), Rgb([255u8, 0u8, 255u8])).hsl()
+    }
+
+    fn evenly(colours: &[Rgb<u8>]) -> Self {
+        let last = (colours.len() - 1).max(1) as f32;
+        ColourMap::sorted(
+            colours
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i as f32 / last, *c))
+                .collect(),
+        )
+    }
+
+    /// Blend between stops in HSL rather than RGB
+    pub fn hsl(mut self) -> Self {
+        self.interpolation = Interpolation::Hsl;
+        self
+    }
+
+    /// Split the map into a number of flat bands rather than a smooth gradient
+    pub fn bands(mut self, bands: u32) -> Self {
+        self.bands = Some(bands.max(1));
+        self
+    }
+
+    /// Return the colour at a position from 0 to 1, positions outside are clamped
+    pub fn colour(&self, ratio: f32) -> Rgb<u8> {
+        let mut ratio = if ratio.is_nan() {
+            0.0
+        } else {
+            ratio.clamp(0.0, 1.0)
+        };
+        if let Some(bands) = self.bands {
+            ratio = if bands == 1 {
+                0.0
+            } else {
+                (ratio * bands as f32).floor().min(bands as f32 - 1.0) / (bands as f32 - 1.0)
+            };
+        }
+
+        let first = self.stops[0];
+        let last = self.stops[self.stops.len() - 1];
+        if ratio <= first.0 {
+            return first.1;
+        }
+        if ratio >= last.0 {
+            return last.1;
+        }
+        let upper = self
+            .stops
+            .iter()
+            .position(|(stop, _)| *stop >= ratio)
+            .unwrap_or(self.stops.len() - 1);
+        let (from, start) = self.stops[upper - 1];
+        let (to, end) = self.stops[upper];
+        let local = if to > from {
+            (ratio - from) / (to - from)
+        } else {
+            0.0
+        };
+
+        match self.interpolation {
+            Interpolation::Rgb => gradient_colour(start, end, local),
+            Interpolation::Hsl => {
+                let (h1, s1, l1) = to_hsl(start);
+                let (h2, s2, l2) = to_hsl(end);
+                from_hsl(
+                    h1 + (h2 - h1) * local,
+                    s1 + (s2 - s1) * local,
+                    l1 + (l2 - l1) * local,
+                )
+            }
+        }
+    }
+}
+
+/// Convert to hue in degrees, saturation and lightness from 0 to 1
+pub fn to_hsl(colour: Rgb<u8>) -> (f32, f32, f32) {
+    let r = colour[0] as f32 / 255.0;
+    let g = colour[1] as f32 / 255.0;
+    let b = colour[2] as f32 / 255.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation, lightness)
+}
+
+pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Rgb<u8> {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+
+    Rgb([
+        ((r + m) * 255.0).round() as u8,
+        ((g + m) * 255.0).round() as u8,
+        ((b + m) * 255.0).round() as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::image::{BLUE, WHITE};
+
+    #[test]
+    fn check_linear_matches_gradient() {
+        let map = ColourMap::linear(WHITE, BLUE);
+
+        assert_eq!(map.colour(0.5), gradient_colour(WHITE, BLUE, 0.5));
+        assert_eq!(map.colour(-1.0), WHITE);
+        assert_eq!(map.colour(2.0), BLUE);
+    }
+
+    #[test]
+    fn check_stops() {
+        let map = ColourMap::viridis();
+
+        assert_eq!(map.colour(0.0), Rgb([68u8, 1u8, 84u8]));
+        assert_eq!(map.colour(0.5), Rgb([33u8, 145u8, 140u8]));
+        assert_eq!(map.colour(1.0), Rgb([253u8, 231u8, 37u8]));
+    }
+
+    #[test]
+    fn check_custom_stops_unsorted() {
+        let map = ColourMap::new(vec![(1.0, BLUE), (0.0, WHITE)]).expect("Invalid colour map");
+
+        assert_eq!(map.colour(0.0), WHITE);
+    }
+
+    #[test]
+    fn check_no_stops() {
+        assert_eq!(
+            ColourMap::new(Vec::new()).unwrap_err(),
+            Error::EmptyColourMap
+        );
+    }
+
+    #[test]
+    fn check_hsl() {
+        let map = ColourMap::rainbow();
+
+        assert_eq!(map.colour(0.0), Rgb([255u8, 0u8, 0u8]));
+        assert_eq!(map.colour(0.4), Rgb([0u8, 255u8, 0u8]));
+        assert_eq!(map.colour(0.8), Rgb([0u8, 0u8, 255u8]));
+    }
+
+    #[test]
+    fn check_bands() {
+        let map = ColourMap::linear(WHITE, BLUE).bands(3);
+
+        assert_eq!(map.colour(0.2), WHITE);
+        assert_eq!(map.colour(0.5), map.colour(0.6));
+        assert_eq!(map.colour(0.9), BLUE);
+    }
+
+    #[test]
+    fn check_hsl_round_trip() {
+        for colour in [Rgb([12u8, 200u8, 99u8]), WHITE, BLUE].iter() {
+            let (h, s, l) = to_hsl(*colour);
+            assert_eq!(from_hsl(h, s, l), *colour);
+        }
+    }
+}
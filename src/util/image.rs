@@ -1,5 +1,27 @@
 use image::Rgb;
 
+pub const WHITE: Rgb<u8> = Rgb([255u8, 255u8, 255u8]);
+pub const BLACK: Rgb<u8> = Rgb([0u8, 0u8, 0u8]);
+pub const GREY: Rgb<u8> = Rgb([128u8, 128u8, 128u8]);
+pub const SILVER: Rgb<u8> = Rgb([192u8, 192u8, 192u8]);
+pub const BLUE: Rgb<u8> = Rgb([0u8, 0u8, 255u8]);
+pub const RED: Rgb<u8> = Rgb([255u8, 0u8, 0u8]);
+
+/// Distinct colours used to tell apart the territories of multiple sources
+pub const PALETTE: [Rgb<u8>; 6] = [
+    BLUE,
+    RED,
+    Rgb([0u8, 160u8, 0u8]),
+    Rgb([255u8, 160u8, 0u8]),
+    Rgb([160u8, 0u8, 160u8]),
+    Rgb([0u8, 160u8, 160u8]),
+];
+
+/// Return the palette colour for a territory owner, wrapping round if there are too many
+pub fn owner_colour(owner: usize) -> Rgb<u8> {
+    PALETTE[owner % PALETTE.len()]
+}
+
 pub fn gradient_colour(start: Rgb<u8>, end: Rgb<u8>, ratio: f32) -> Rgb<u8> {
     Rgb([
         (start[0] as f32 * (1f32 - ratio) + end[0] as f32 * ratio) as u8,
@@ -12,9 +34,6 @@ pub fn gradient_colour(start: Rgb<u8>, end: Rgb<u8>, ratio: f32) -> Rgb<u8> {
 mod tests {
     use super::*;
 
-    const WHITE: Rgb<u8> = Rgb([255u8, 255u8, 255u8]);
-    const BLUE: Rgb<u8> = Rgb([0u8, 0u8, 255u8]);
-
     #[test]
     fn check_gradient_zero() {
         assert_eq!(gradient_colour(WHITE, BLUE, 0.0), WHITE);
@@ -25,6 +44,12 @@ mod tests {
         assert_eq!(gradient_colour(WHITE, BLUE, 1.0), BLUE);
     }
 
+    #[test]
+    fn check_owner_colour() {
+        assert_eq!(owner_colour(0), BLUE);
+        assert_eq!(owner_colour(PALETTE.len()), BLUE);
+    }
+
     #[test]
     fn check_gradient_half() {
         assert_eq!(gradient_colour(WHITE, BLUE, 0.5), Rgb([127, 127, 255]));
@@ -1,2 +1,5 @@
+pub mod colourmap;
+pub mod font;
 pub mod image;
 pub mod math;
+pub mod svg;
@@ -0,0 +1,176 @@
+use image::Rgb;
+use std::fmt::Write;
+
+pub type Point = (f32, f32);
+pub type Segment = (Point, Point);
+
+/// Minimal SVG document builder, elements are appended in drawing order
+pub struct Document {
+    width: u32,
+    height: u32,
+    antialias: bool,
+    /// printed width and height in inches, otherwise the size is in pixels
+    inches: Option<(f32, f32)>,
+    body: String,
+}
+
+#[allow(dead_code)]
+impl Document {
+    pub fn new(width: u32, height: u32, antialias: bool) -> Document {
+        Document {
+            width,
+            height,
+            antialias,
+            inches: None,
+            body: String::new(),
+        }
+    }
+
+    /// Set the printed size of the document, the pixel size is kept as the view box
+    pub fn inches(mut self, width: f32, height: f32) -> Document {
+        self.inches = Some((width, height));
+        self
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, fill: Rgb<u8>) {
+        let _ = writeln!(
+            self.body,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            hex(fill)
+        );
+    }
+
+    /// Add a path built from a list of line segments
+    pub fn lines(&mut self, segments: &[Segment], stroke: Rgb<u8>, width: f32) {
+        if segments.is_empty() {
+            return;
+        }
+        let mut d = String::new();
+        for ((x1, y1), (x2, y2)) in segments {
+            let _ = write!(d, "M{} {}L{} {}", x1, y1, x2, y2);
+        }
+        let _ = writeln!(
+            self.body,
+            r#"  <path d="{}" stroke="{}" stroke-width="{}" stroke-linecap="square" fill="none"/>"#,
+            d,
+            hex(stroke),
+            width
+        );
+    }
+
+    pub fn polyline(&mut self, points: &[Point], stroke: Rgb<u8>, width: f32) {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+        let _ = writeln!(
+            self.body,
+            r#"  <polyline points="{}" stroke="{}" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round" fill="none"/>"#,
+            points.join(" "),
+            hex(stroke),
+            width
+        );
+    }
+
+    /// Add text centred on the point
+    pub fn text(&mut self, x: f32, y: f32, size: f32, fill: Rgb<u8>, text: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"  <text x="{}" y="{}" font-size="{}" font-family="sans-serif" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+            x,
+            y,
+            size,
+            hex(fill),
+            escape(text)
+        );
+    }
+
+    /// Add another SVG document as a nested element with its top left corner at the point
+    pub fn embed(&mut self, x: f32, y: f32, svg: &str) {
+        let svg = svg
+            .trim_end()
+            .replacen("<svg ", &format!(r#"<svg x="{}" y="{}" "#, x, y), 1);
+        let _ = writeln!(self.body, "{}", svg);
+    }
+
+    pub fn finish(self) -> String {
+        let (width, height) = match self.inches {
+            Some((w, h)) => (format!("{}in", w), format!("{}in", h)),
+            None => (self.width.to_string(), self.height.to_string()),
+        };
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {w} {h}\" shape-rendering=\"{}\">\n{}</svg>\n",
+            width,
+            height,
+            if self.antialias {
+                "geometricPrecision"
+            } else {
+                "crispEdges"
+            },
+            self.body,
+            w = self.width,
+            h = self.height
+        )
+    }
+}
+
+/// Escape the characters that have a meaning in XML text and attributes
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Return the colour as an SVG hex string, e.g. `#0000ff`
+pub fn hex(colour: Rgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_hex() {
+        assert_eq!(hex(Rgb([0u8, 128u8, 255u8])), "#0080ff");
+    }
+
+    #[test]
+    fn check_escape() {
+        assert_eq!(escape(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+
+    #[test]
+    fn check_document_inches() {
+        let mut document = Document::new(200, 100, true).inches(2.0, 1.0);
+        document.text(100.0, 50.0, 12.0, Rgb([0u8, 0u8, 0u8]), "A & B");
+        document.embed(10.0, 20.0, &Document::new(5, 5, true).finish());
+
+        let svg = document.finish();
+
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="2in" height="1in" viewBox="0 0 200 100""#
+        ));
+        assert!(svg.contains(">A &amp; B</text>"));
+        assert!(svg.contains(r#"<svg x="10" y="20" xmlns"#));
+    }
+
+    #[test]
+    fn check_document() {
+        let mut document = Document::new(20, 10, false);
+        document.rect(0.0, 0.0, 20.0, 10.0, Rgb([255u8, 255u8, 255u8]));
+        document.lines(&[((0.0, 0.0), (20.0, 0.0))], Rgb([0u8, 0u8, 0u8]), 1.0);
+        document.lines(&[], Rgb([0u8, 0u8, 0u8]), 1.0);
+
+        assert_eq!(
+            document.finish(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10" viewBox="0 0 20 10" shape-rendering="crispEdges">
+  <rect x="0" y="0" width="20" height="10" fill="#ffffff"/>
+  <path d="M0 0L20 0" stroke="#000000" stroke-width="1" stroke-linecap="square" fill="none"/>
+</svg>
+"##
+        );
+    }
+}